@@ -0,0 +1,68 @@
+// Benchmarks for the generators, drawing a maze to an image and the solving strategies.
+//
+// cargo bench
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use indicatif::ProgressBar;
+use pathfinding_simulation::maze::Maze;
+use pathfinding_simulation::save::generate_maze_image_with_bar;
+use pathfinding_simulation::solve::{solve, Strategy};
+
+const SIZES: [usize; 3] = [25, 50, 100];
+
+type Generator = fn(&mut Maze, Option<&ProgressBar>);
+
+const GENERATORS: [(&str, Generator); 3] = [
+    ("dfs", Maze::dfs_maze_with_bar),
+    ("prims", Maze::prims_maze_with_bar),
+    ("kruskals", Maze::kruskals_maze_with_bar),
+];
+
+/// A finished maze with a few loops, so the solvers have choices to make.
+fn maze(size: usize) -> Maze {
+    let mut maze = Maze::new(size, size);
+    maze.prims_maze_with_bar(None);
+    maze.braid(0.1);
+    maze
+}
+
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    for size in SIZES {
+        for (name, generate) in GENERATORS {
+            // Without a progress bar, so only the generator itself is timed
+            group.bench_function(BenchmarkId::new(name, size), |b| {
+                b.iter_batched(|| Maze::new(size, size), |mut maze| generate(&mut maze, None), BatchSize::SmallInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+fn image(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_maze_image_with_bar");
+    for size in SIZES {
+        let maze = maze(size);
+        // Same cell size and wall thickness as save_maze_image
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| generate_maze_image_with_bar(&maze, 5, 1, None))
+        });
+    }
+    group.finish();
+}
+
+fn solving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    for size in SIZES {
+        let maze = maze(size);
+        for strategy in Strategy::ALL {
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", strategy), size), &maze, |b, maze| {
+                b.iter(|| solve(maze, strategy, (0, 0), (size - 1, size - 1)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation, image, solving);
+criterion_main!(benches);
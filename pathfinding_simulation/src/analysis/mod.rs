@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::maze::{Maze, Topology, MAX_WALLS};
+
+// The report printed after a maze is generated. Everything is worked out from
+// how many open walls each node has: 1 for a dead end, 2 for a corridor,
+// 3 for a junction, more than that for a crossroads.
+
+#[derive(Default)]
+pub struct MazeStats {
+    pub topology: Topology,
+    pub cells: usize,
+    pub dead_ends: usize,
+    pub corridors: usize,
+    pub junctions: usize,
+    pub crossroads: usize,
+    pub dead_end_ratio: f64,
+    pub average_corridor: f64, // Length of a run of corridor nodes, on average
+    pub river_factor: f64,     // Nodes walked from a dead end until the path branches, on average
+    pub solution_length: Option<usize>,
+    pub tortuosity: Option<f64>, // Solution length compared to walking there with no walls
+    pub axis_passages: [usize; MAX_WALLS / 2], // Open walls along each axis, numbered like the lower wall on it
+    pub direction_bias: f64, // Between -1 (all vertical) and 1 (all horizontal), diagonals split by how far they lean
+}
+
+pub fn analyze(maze: &Maze, start: (usize, usize), goal: (usize, usize)) -> MazeStats {
+    let axes = maze.topology.wall_count() / 2;
+    let mut stats = MazeStats {
+        topology: maze.topology,
+        cells: maze.width * maze.height,
+        ..MazeStats::default()
+    };
+
+    let mut degree = vec![vec![0; maze.width]; maze.height];
+    for node in maze.grid.iter().flatten() {
+        let open = maze.open_neighbors(node.x, node.y);
+        degree[node.y][node.x] = open.len();
+        match open.len() {
+            1 => stats.dead_ends += 1,
+            2 => stats.corridors += 1,
+            3 => stats.junctions += 1,
+            4.. => stats.crossroads += 1,
+            _ => {}
+        }
+        for wall in 0..maze.topology.wall_count() {
+            match maze.passage((node.x, node.y), wall) {
+                Some((nx, ny)) if (ny, nx) > (node.y, node.x) => stats.axis_passages[wall % axes] += 1,
+                _ => {} // Closed, or counted from the other side already
+            }
+        }
+    }
+    stats.dead_end_ratio = stats.dead_ends as f64 / stats.cells as f64;
+
+    let mut in_run = vec![vec![false; maze.width]; maze.height];
+    let mut runs = 0;
+    for node in maze.grid.iter().flatten() {
+        if degree[node.y][node.x] != 2 || in_run[node.y][node.x] {
+            continue;
+        }
+        runs += 1;
+        in_run[node.y][node.x] = true;
+        let mut stack = vec![(node.x, node.y)];
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in maze.open_neighbors(x, y) {
+                if degree[ny][nx] == 2 && !in_run[ny][nx] {
+                    in_run[ny][nx] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+    }
+    if runs > 0 {
+        stats.average_corridor = stats.corridors as f64 / runs as f64;
+    }
+
+    let mut walked = 0;
+    for node in maze.grid.iter().flatten().filter(|node| degree[node.y][node.x] == 1) {
+        let dead_end = (node.x, node.y);
+        let mut previous = dead_end;
+        let mut current = dead_end;
+        loop {
+            walked += 1;
+            let mut onward = maze.open_neighbors(current.0, current.1);
+            onward.retain(|&next| next != previous);
+            if onward.len() != 1 {
+                break;
+            }
+            let next = onward[0];
+            if next == dead_end || degree[next.1][next.0] > 2 {
+                break;
+            }
+            previous = current;
+            current = next;
+        }
+    }
+    if stats.dead_ends > 0 {
+        stats.river_factor = walked as f64 / stats.dead_ends as f64;
+    }
+
+    stats.solution_length = distance(maze, start, goal);
+    let straight = maze.topology.distance(start, goal);
+    if straight > 0 {
+        stats.tortuosity = stats.solution_length.map(|length| length as f64 / straight as f64);
+    }
+
+    // A hex diagonal is a quarter horizontal and three quarters vertical, so an
+    // unbiased hex maze comes out at 0 just like a square one
+    let (mut horizontal, mut vertical) = (0.0, 0.0);
+    for (axis, &count) in stats.axis_passages[..axes].iter().enumerate() {
+        let (dx, dy) = maze.topology.direction(axis);
+        horizontal += count as f64 * dx * dx;
+        vertical += count as f64 * dy * dy;
+    }
+    if horizontal + vertical > 0.0 {
+        stats.direction_bias = (horizontal - vertical) / (horizontal + vertical);
+    }
+    stats
+}
+
+/// Steps from `start` to `goal` found by a breadth first search, None if there is no way through.
+fn distance(maze: &Maze, start: (usize, usize), goal: (usize, usize)) -> Option<usize> {
+    let mut distance = vec![vec![None; maze.width]; maze.height];
+    distance[start.1][start.0] = Some(0);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some((x, y)) = queue.pop_front() {
+        let next = distance[y][x].map(|d: usize| d + 1);
+        for (nx, ny) in maze.open_neighbors(x, y) {
+            if distance[ny][nx].is_none() {
+                distance[ny][nx] = next;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    distance[goal.1][goal.0]
+}
+
+impl fmt::Display for MazeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |count: usize| 100.0 * count as f64 / self.cells as f64;
+        writeln!(f, "Cells:            {}", self.cells)?;
+        writeln!(f, "Dead ends:        {} ({:.1}%)", self.dead_ends, 100.0 * self.dead_end_ratio)?;
+        writeln!(f, "Corridors:        {} ({:.1}%)", self.corridors, percent(self.corridors))?;
+        writeln!(f, "Junctions:        {} ({:.1}%)", self.junctions, percent(self.junctions))?;
+        writeln!(f, "Crossroads:       {} ({:.1}%)", self.crossroads, percent(self.crossroads))?;
+        writeln!(f, "Average corridor: {:.2} cells", self.average_corridor)?;
+        writeln!(f, "River factor:     {:.2}", self.river_factor)?;
+        match self.solution_length {
+            Some(length) => writeln!(f, "Solution length:  {}", length)?,
+            None => writeln!(f, "Solution length:  unreachable")?,
+        }
+        if let Some(tortuosity) = self.tortuosity {
+            writeln!(f, "Tortuosity:       {:.2}", tortuosity)?;
+        }
+        let names: &[&str] = match self.topology {
+            Topology::Square => &["vertical", "horizontal"],
+            Topology::Hex => &["rising", "horizontal", "falling"],
+        };
+        let passages: Vec<String> = names
+            .iter()
+            .zip(self.axis_passages)
+            .map(|(name, count)| format!("{} {}", count, name))
+            .collect();
+        write!(f, "Direction bias:   {:+.3} ({} passages)", self.direction_bias, passages.join(", "))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_counts_add_up() {
+        let mut maze = Maze::new(16, 12);
+        maze.prims_maze();
+
+        let stats = analyze(&maze, (0, 0), (15, 11));
+        assert_eq!(stats.dead_ends + stats.corridors + stats.junctions + stats.crossroads, stats.cells);
+        assert_eq!(stats.axis_passages.iter().sum::<usize>(), stats.cells - 1);
+        assert!(stats.tortuosity.unwrap() >= 1.0);
+    }
+
+    #[test]
+    fn test_single_corridor() {
+        let mut maze = Maze::new(5, 1);
+        for x in 0..4 {
+            maze.grid[0][x].walls[1] = false;
+            maze.grid[0][x + 1].walls[3] = false;
+        }
+
+        let stats = analyze(&maze, (0, 0), (4, 0));
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.corridors, 3);
+        assert_eq!(stats.average_corridor, 3.0);
+        assert_eq!(stats.solution_length, Some(4));
+        assert_eq!(stats.direction_bias, 1.0);
+        assert!(stats.to_string().contains("Dead ends:        2 (40.0%)"));
+        assert!(stats.to_string().contains("(0 vertical, 4 horizontal passages)"));
+    }
+
+    #[test]
+    fn test_hex_diagonals_lean_both_ways() {
+        // A single hex column zigzags down, every passage a diagonal
+        let mut column = Maze::with_topology(1, 5, Topology::Hex);
+        column.braid(1.0);
+        let stats = analyze(&column, (0, 0), (0, 4));
+        assert_eq!(stats.axis_passages, [2, 0, 2]);
+        assert!((stats.direction_bias + 0.5).abs() < 1e-9);
+        assert!(stats.to_string().contains("(2 rising, 0 horizontal, 2 falling passages)"));
+
+        let mut row = Maze::with_topology(5, 1, Topology::Hex);
+        row.braid(1.0);
+        assert_eq!(analyze(&row, (0, 0), (4, 0)).direction_bias, 1.0);
+
+        let mut maze = Maze::with_topology(16, 12, Topology::Hex);
+        maze.prims_maze();
+        let stats = analyze(&maze, (0, 0), (15, 11));
+        assert_eq!(stats.axis_passages.iter().sum::<usize>(), stats.cells - 1);
+    }
+}
@@ -0,0 +1,6 @@
+// lib.rs
+
+pub mod analysis;
+pub mod maze;
+pub mod save;
+pub mod solve;
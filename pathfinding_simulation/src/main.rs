@@ -1,11 +1,11 @@
 use console::{Term, Key};
 use std::io::{self};
 
-mod maze;
-mod save;
+use pathfinding_simulation::{analysis, maze, save, solve};
 
-use maze::Maze;
-use save::save_maze_image;
+use maze::{Maze, Topology};
+use save::{save_maze_image, save_solution_image};
+use solve::Strategy;
 
 #[derive(PartialEq,Debug)]
 enum MenuState {
@@ -48,14 +48,25 @@ fn main() -> io::Result<()> {
                 }
             }
             Some(MenuState::AlgorithmMenu) => {
-                let options = ["Back", "A*", "Dijkstra's", "DFS", "BFS"];
+                let options = [
+                    "Back",
+                    "A*",
+                    "Dijkstra's",
+                    "DFS",
+                    "BFS",
+                    "Left-hand wall follower",
+                    "Right-hand wall follower",
+                    "Pledge",
+                    "Tremaux",
+                    "Dead-end filling",
+                ];
                 print_menu(&options, selected_index);
                 if handle_key_input(&term, &mut selected_index, options.len(), &mut menu_stack)? {
                     break;
                 }
             }
             Some(MenuState::MazeMenu) => {
-                let options = ["Back", "Random DFS Maze", "Prims Maze"];
+                let options = ["Back", "Random DFS Maze", "Prims Maze", "Kruskals Maze", "Hexagonal Prims Maze"];
                 print_menu(&options, selected_index);
                 if handle_key_input(&term, &mut selected_index, options.len(), &mut menu_stack)? {
                     break;
@@ -128,6 +139,12 @@ fn handle_key_input(
                     2 => println!("Running Dijkstra's algorithm..."),
                     3 => println!("Running DFS algorithm..."),
                     4 => println!("Running BFS algorithm..."),
+                    5..=9 => {
+                        run_strategy(Strategy::ALL[*selected_index - 5]);
+                        println!("Press Enter to continue...");
+                        let mut input = String::new();
+                        let _ = std::io::stdin().read_line(&mut input);
+                    }
                     _ => {}
                 },
                 Some(MenuState::MazeMenu) => match *selected_index {
@@ -137,26 +154,22 @@ fn handle_key_input(
                     1 => {
                         let mut maze = Maze::new(20, 20);
                         maze.dfs_maze();
-                        if let Err(e) = save_maze_image(&maze, "maze.png") {
-                            eprintln!("Failed to save maze: {}", e);
-                        } else {
-                            println!("Maze saved successfully as maze.png");
-                        }
-                        println!("Press Enter to continue...");
-                        let mut input = String::new();
-                        let _ = std::io::stdin().read_line(&mut input);
+                        report_maze(&maze);
                     }
                     2 => {
                         let mut maze = Maze::new(20,20);
                         maze.prims_maze();
-                        if let Err(e) = save_maze_image(&maze, "maze.png") {
-                            eprintln!("Failed to save maze: {}", e);
-                        } else {
-                            println!("Maze saved successfully as maze.png");
-                        }
-                        println!("Press Enter to continue...");
-                        let mut input = String::new();
-                        let _ = std::io::stdin().read_line(&mut input);
+                        report_maze(&maze);
+                    }
+                    3 => {
+                        let mut maze = Maze::new(20, 20);
+                        maze.kruskals_maze();
+                        report_maze(&maze);
+                    }
+                    4 => {
+                        let mut maze = Maze::with_topology(20, 20, Topology::Hex);
+                        maze.prims_maze();
+                        report_maze(&maze);
                     }
                     _ => {}
                 },
@@ -173,6 +186,46 @@ fn handle_key_input(
     Ok(false)
 }
 
+/// Saves a freshly generated maze as maze.png, prints its check and analysis,
+/// then waits for Enter.
+fn report_maze(maze: &Maze) {
+    if let Err(e) = save_maze_image(maze, "maze.png") {
+        eprintln!("Failed to save maze: {}", e);
+    } else {
+        println!("Maze saved successfully as maze.png");
+    }
+    println!("Maze check: {}", maze.validate());
+    println!("{}", analysis::analyze(maze, (0, 0), (maze.width - 1, maze.height - 1)));
+    println!("Press Enter to continue...");
+    let mut input = String::new();
+    let _ = std::io::stdin().read_line(&mut input);
+}
+
+/// Solves a fresh maze with a few loops knocked into it, starting from the
+/// middle so a wall follower has something to get lost around.
+fn run_strategy(strategy: Strategy) {
+    let mut maze = Maze::new(20, 20);
+    maze.prims_maze();
+    maze.braid(0.1);
+
+    let start = (maze.width / 2, maze.height / 2);
+    let goal = (maze.width - 1, maze.height - 1);
+    println!("Running {} from {:?} to {:?}...", strategy.name(), start, goal);
+
+    let solution = solve::solve(&maze, strategy, start, goal);
+    match &solution.path {
+        Some(path) => {
+            println!("Reached the goal after {} moves, path length {}", solution.moves, path.len() - 1);
+            match save_solution_image(&maze, path, "solution.png") {
+                Ok(()) => println!("Solution saved as solution.png"),
+                Err(e) => eprintln!("Failed to save solution: {}", e),
+            }
+        }
+        None if solution.looped => println!("Walked in a loop after {} moves, the goal can't be reached this way", solution.moves),
+        None => println!("No path found after {} moves", solution.moves),
+    }
+}
+
 
 
 
@@ -190,10 +243,23 @@ mod main_tests {
 
     #[test]
     fn test_algorithm_menu_options() {
-        let menu_options = vec!["Back", "A*", "Dijkstra's", "DFS", "BFS"];
-        assert_eq!(menu_options.len(), 5);
+        let menu_options = vec![
+            "Back",
+            "A*",
+            "Dijkstra's",
+            "DFS",
+            "BFS",
+            "Left-hand wall follower",
+            "Right-hand wall follower",
+            "Pledge",
+            "Tremaux",
+            "Dead-end filling",
+        ];
+        assert_eq!(menu_options.len(), 10);
         assert_eq!(menu_options[1], "A*");
         assert_eq!(menu_options[4], "BFS");
+        assert_eq!(menu_options[5], Strategy::ALL[0].name());
+        assert_eq!(menu_options[9], Strategy::ALL[4].name());
     }
 
     #[test]
@@ -262,8 +328,20 @@ mod main_tests {
     fn test_maze_creation_and_saving() {
         let mut maze = Maze::new(10, 10);
         maze.dfs_maze();
-        let result = save_maze_image(&maze, "test_maze.png");
+        let path = std::env::temp_dir().join("test_maze.png");
+        let result = save_maze_image(&maze, path.to_str().unwrap());
         assert!(result.is_ok(), "Failed to save the maze image");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hex_maze_creation_and_saving() {
+        let mut maze = Maze::with_topology(10, 10, Topology::Hex);
+        maze.prims_maze();
+        let path = std::env::temp_dir().join("test_hex_maze.png");
+        let result = save_maze_image(&maze, path.to_str().unwrap());
+        assert!(result.is_ok(), "Failed to save the hex maze image");
+        std::fs::remove_file(path).unwrap();
     }
 
     #[test]
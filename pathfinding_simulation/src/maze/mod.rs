@@ -1,21 +1,32 @@
 
 #[cfg(not(test))]
 use indicatif::{ProgressBar, ProgressStyle};
-use rand::thread_rng;
+
+#[cfg(test)]
+use indicatif::ProgressBar;
+use rand::{thread_rng, Rng};
 use rand::seq::{IteratorRandom,SliceRandom};
 mod node;
+mod validate;
 
 pub use node::Node;
+pub use pathfinding_simulation_core::{DisjointSet, Topology, MAX_WALLS};
 
 
 pub struct Maze {
     pub width: usize,
     pub height: usize,
+    pub topology: Topology,
     pub grid: Vec<Vec<Node>>,
 }
 
 impl Maze {
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_topology(width, height, Topology::Square)
+    }
+
+    /// A maze whose cells have the shape `topology` gives them, every wall standing.
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
         let grid = (0..height)
             .map(|y| {
                 (0..width)
@@ -23,110 +34,151 @@ impl Maze {
                         x,
                         y,
                         visited: false,
-                        walls: [true, true, true, true],
+                        walls: [true; MAX_WALLS],
                     })
                     .collect()
             })
             .collect();
 
-        Maze { width, height, grid }
+        Maze { width, height, topology, grid }
     }
 
-    pub fn dfs_maze(&mut self) {
+    /// The cell on the other side of `wall` of (x, y), standing or not, or None
+    /// if the wall is on the edge of the maze.
+    pub fn neighbor(&self, (x, y): (usize, usize), wall: usize) -> Option<(usize, usize)> {
+        let (dx, dy) = self.topology.offset(y, wall);
+        let nx = x.checked_add_signed(dx).filter(|&nx| nx < self.width)?;
+        let ny = y.checked_add_signed(dy).filter(|&ny| ny < self.height)?;
+        Some((nx, ny))
+    }
 
-        let mut stack: Vec<(usize,usize)>  = Vec::new();
-        let mut rng = thread_rng();
+    /// The cell reached by going through `wall` of (x, y), if that wall is open.
+    pub fn passage(&self, (x, y): (usize, usize), wall: usize) -> Option<(usize, usize)> {
+        if self.grid[y][x].walls[wall] {
+            return None;
+        }
+        self.neighbor((x, y), wall)
+    }
 
+    /// Neighbouring cells that can be reached from (x, y) without crossing a wall.
+    pub fn open_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        (0..self.topology.wall_count()).filter_map(|wall| self.passage((x, y), wall)).collect()
+    }
 
-        #[cfg(not(test))]
-        let bar = ProgressBar::new(self.height as u64 *self.width as u64);
+    /// Every wall between two cells once, as (x, y, wall) of the cell that comes first row by row.
+    pub fn interior_walls(&self) -> Vec<(usize, usize, usize)> {
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for wall in 0..self.topology.wall_count() {
+                    if let Some((nx, ny)) = self.neighbor((x, y), wall) {
+                        if (ny, nx) > (y, x) {
+                            walls.push((x, y, wall));
+                        }
+                    }
+                }
+            }
+        }
+        walls
+    }
+
+    /// Opens `wall` of (x, y) from both sides.
+    fn remove_wall(&mut self, (x, y): (usize, usize), wall: usize) {
+        self.grid[y][x].walls[wall] = false;
+        if let Some((nx, ny)) = self.neighbor((x, y), wall) {
+            self.grid[ny][nx].walls[self.topology.opposite(wall)] = false;
+        }
+    }
 
+    /// The wall of `from` that separates it from the neighbouring cell `to`.
+    fn wall_between(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        (0..self.topology.wall_count())
+            .find(|&wall| self.neighbor(from, wall) == Some(to))
+            .expect("cells should be neighbours")
+    }
 
+    /// Knocks down a random share of the interior walls, turning a perfect maze
+    /// into one with loops.
+    pub fn braid(&mut self, fraction: f32) {
+        let mut rng = thread_rng();
+        for (x, y, wall) in self.interior_walls() {
+            if self.grid[y][x].walls[wall] && rng.gen::<f32>() < fraction {
+                self.remove_wall((x, y), wall);
+            }
+        }
+    }
+
+    pub fn dfs_maze(&mut self) {
         #[cfg(not(test))]
-        bar.set_style(ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-        .unwrap()
-        .progress_chars("##-"));
+        let bar = generation_bar(self.height as u64 * self.width as u64);
         #[cfg(not(test))]
-        bar.set_message("Generating maze...");
-        let start_x = rand::random::<usize>() % self.width as usize;
-        let start_y = rand::random::<usize>() % self.height as usize;
+        self.dfs_maze_with_bar(Some(&bar));
+        #[cfg(test)]
+        self.dfs_maze_with_bar(None);
+        #[cfg(not(test))]
+        bar.finish();
+    }
+
+    pub fn dfs_maze_with_bar(&mut self, bar: Option<&ProgressBar>) {
+
+        let mut stack: Vec<(usize,usize)>  = Vec::new();
+        let mut rng = thread_rng();
+
+        let start_x = rand::random::<usize>() % self.width;
+        let start_y = rand::random::<usize>() % self.height;
 
         self.grid[start_y][start_x].visited = true;
 
-        stack.push((start_y,start_x));
+        stack.push((start_x,start_y));
 
         
         while let Some((x,y)) = stack.pop() {
-            let mut neighbors = Vec::new();
+            let neighbors: Vec<(usize, usize, usize)> = (0..self.topology.wall_count())
+                .filter_map(|wall| self.neighbor((x, y), wall).map(|(nx, ny)| (nx, ny, wall)))
+                .filter(|&(nx, ny, _)| !self.grid[ny][nx].visited)
+                .collect();
 
-             if x > 0 && !self.grid[y][x - 1].visited {
-                neighbors.push((x - 1, y, 3, 1)); 
-            }
-            if x < self.width - 1 && !self.grid[y][x + 1].visited {
-                neighbors.push((x + 1, y, 1, 3)); 
-            }
-            if y > 0 && !self.grid[y - 1][x].visited {
-                neighbors.push((x, y - 1, 0, 2)); 
-            }
-            if y < self.height - 1 && !self.grid[y + 1][x].visited {
-                neighbors.push((x, y + 1, 2, 0)); 
-            }
-
-            if let Some(&(nx, ny, current_wall, neighbor_wall)) = neighbors.choose(&mut rng) {
-                self.grid[y][x].walls[current_wall] = false;
-                self.grid[ny][nx].walls[neighbor_wall] = false;
+            if let Some(&(nx, ny, current_wall)) = neighbors.choose(&mut rng) {
+                self.remove_wall((x, y), current_wall);
 
                 self.grid[ny][nx].visited = true;
                 stack.push((x, y));
                 stack.push((nx, ny));
 
-
-
-                #[cfg(not(test))]
-                bar.inc(1);
+                if let Some(bar) = bar {
+                    bar.inc(1);
+                }
             }
 
         }
 
-
-        #[cfg(not(test))]
-        bar.finish();
-
     }
 
 
     pub fn prims_maze(&mut self) {
-        let mut walls: Vec<(usize, usize, usize, usize)> = Vec::new();
-        let mut rng = thread_rng();
-    
         #[cfg(not(test))]
-        let bar = ProgressBar::new((self.height * self.width) as u64);
-    
+        let bar = generation_bar((self.height * self.width) as u64);
         #[cfg(not(test))]
-        bar.set_style(
-            ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-                .unwrap()
-                .progress_chars("##-"),
-        );
+        self.prims_maze_with_bar(Some(&bar));
+        #[cfg(test)]
+        self.prims_maze_with_bar(None);
         #[cfg(not(test))]
-        bar.set_message("Generating maze...");
+        bar.finish();
+    }
+
+    pub fn prims_maze_with_bar(&mut self, bar: Option<&ProgressBar>) {
+        let mut walls: Vec<(usize, usize, usize, usize)> = Vec::new();
+        let mut rng = thread_rng();
     
         let start_x = rand::random::<usize>() % self.width;
         let start_y = rand::random::<usize>() % self.height;
     
         self.grid[start_y][start_x].visited = true;
     
-        if start_x > 0 {
-            walls.push((start_x, start_y, start_x - 1, start_y)); // Left wall
-        }
-        if start_x < self.width - 1 {
-            walls.push((start_x, start_y, start_x + 1, start_y)); // Right wall
-        }
-        if start_y > 0 {
-            walls.push((start_x, start_y, start_x, start_y - 1)); // Top wall
-        }
-        if start_y < self.height - 1 {
-            walls.push((start_x, start_y, start_x, start_y + 1)); // Bottom wall
+        for wall in 0..self.topology.wall_count() {
+            if let Some((nx, ny)) = self.neighbor((start_x, start_y), wall) {
+                walls.push((start_x, start_y, nx, ny));
+            }
         }
     
         
@@ -135,50 +187,79 @@ impl Maze {
 
         while !walls.is_empty() {
             if let Some(index) = (0..walls.len()).choose(&mut rng) {
-                let (x1, y1, x2, y2) = walls.remove(index);
+                // The frontier is picked from at random, so its order doesn't matter
+                // and the last wall can fill the gap instead of shifting the rest
+                let (x1, y1, x2, y2) = walls.swap_remove(index);
     
                 if !self.grid[y2][x2].visited {
                     self.grid[y2][x2].visited = true;
     
-                    if x1 == x2 {
-                        if y1 > y2 {
-                            self.grid[y1][x1].walls[0] = false; // Top wall
-                            self.grid[y2][x2].walls[2] = false; // Bottom wall
-                        } else {
-                            self.grid[y1][x1].walls[2] = false; // Bottom wall
-                            self.grid[y2][x2].walls[0] = false; // Top wall
-                        }
-                    } else {
-                        if x1 > x2 {
-                            self.grid[y1][x1].walls[3] = false; // Left wall
-                            self.grid[y2][x2].walls[1] = false; // Right wall
-                        } else {
-                            self.grid[y1][x1].walls[1] = false; // Right wall
-                            self.grid[y2][x2].walls[3] = false; // Left wall
-                        }
-                    }
+                    let wall = self.wall_between((x1, y1), (x2, y2));
+                    self.remove_wall((x1, y1), wall);
     
-                    if x2 > 0 && !self.grid[y2][x2 - 1].visited {
-                        walls.push((x2, y2, x2 - 1, y2)); 
-                    }
-                    if x2 < self.width - 1 && !self.grid[y2][x2 + 1].visited {
-                        walls.push((x2, y2, x2 + 1, y2)); 
-                    }
-                    if y2 > 0 && !self.grid[y2 - 1][x2].visited {
-                        walls.push((x2, y2, x2, y2 - 1)); 
+                    for wall in 0..self.topology.wall_count() {
+                        if let Some((nx, ny)) = self.neighbor((x2, y2), wall) {
+                            if !self.grid[ny][nx].visited {
+                                walls.push((x2, y2, nx, ny));
+                            }
+                        }
                     }
-                    if y2 < self.height - 1 && !self.grid[y2 + 1][x2].visited {
-                        walls.push((x2, y2, x2, y2 + 1)); 
+                    if let Some(bar) = bar {
+                        bar.inc(1);
                     }
-                    #[cfg(not(test))]
-                    bar.inc(1);
                 }
             }
         }
+    }
+
+    /// Kruskal's algorithm: every interior wall in random order, knocked down
+    /// whenever the cells on either side aren't connected yet.
+    pub fn kruskals_maze(&mut self) {
+        #[cfg(not(test))]
+        let bar = generation_bar((self.width * self.height).saturating_sub(1) as u64);
+        #[cfg(not(test))]
+        self.kruskals_maze_with_bar(Some(&bar));
+        #[cfg(test)]
+        self.kruskals_maze_with_bar(None);
         #[cfg(not(test))]
         bar.finish();
     }
 
+    pub fn kruskals_maze_with_bar(&mut self, bar: Option<&ProgressBar>) {
+        let mut rng = thread_rng();
+        let mut sets = DisjointSet::new(self.width * self.height);
+
+        // Each interior wall once, as a wall of the cell that comes first
+        for node in self.grid.iter_mut().flatten() {
+            node.visited = true;
+        }
+        let mut edges = self.interior_walls();
+        edges.shuffle(&mut rng);
+
+        for (x, y, wall) in edges {
+            let (nx, ny) = self.neighbor((x, y), wall).unwrap();
+            if sets.union(y * self.width + x, ny * self.width + nx) {
+                self.remove_wall((x, y), wall);
+
+                if let Some(bar) = bar {
+                    bar.inc(1);
+                }
+            }
+        }
+    }
+
+}
+
+#[cfg(not(test))]
+fn generation_bar(len: u64) -> ProgressBar {
+    let bar = ProgressBar::new(len);
+    bar.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+    bar.set_message("Generating maze...");
+    bar
 }
 
 
@@ -198,7 +279,7 @@ mod tests {
         for row in maze.grid.iter() {
             for node in row.iter() {
                 assert!(!node.visited);
-                assert_eq!(node.walls, [true, true, true, true]);
+                assert_eq!(node.walls[..4], [true, true, true, true]);
             }
         }
     }
@@ -213,6 +294,8 @@ mod tests {
                 assert!(node.visited);
             }
         }
+        let validation = maze.validate();
+        assert!(validation.is_perfect(), "{}", validation);
     }
 
     #[test]
@@ -250,6 +333,9 @@ mod tests {
             }
         }
         assert!(has_open_wall, "Maze should have at least one open wall between cells.");
+
+        let validation = maze.validate();
+        assert!(validation.is_perfect(), "{}", validation);
     }
 
     #[test]
@@ -258,7 +344,7 @@ mod tests {
         small_maze.prims_maze();
 
         assert!(small_maze.grid[0][0].visited);
-        assert_eq!(small_maze.grid[0][0].walls, [true, true, true, true]); 
+        assert_eq!(small_maze.grid[0][0].walls[..4], [true, true, true, true]); 
 
         let mut large_maze = Maze::new(1000, 1000);
         large_maze.prims_maze();
@@ -293,6 +379,50 @@ mod tests {
         assert!(!identical, "Mazes generated by Prim's algorithm should be different.");
     }
 
+    #[test]
+    fn test_kruskals_maze() {
+        let mut maze = Maze::new(12, 7);
+        maze.kruskals_maze();
+
+        let validation = maze.validate();
+        assert!(validation.is_perfect(), "{}", validation);
+
+        let mut single = Maze::new(1, 1);
+        single.kruskals_maze();
+        assert_eq!(single.grid[0][0].walls[..4], [true, true, true, true]);
+
+        // No recursion in the union-find, so a large grid is fine
+        let mut large_maze = Maze::new(1000, 1000);
+        large_maze.kruskals_maze();
+        assert!(large_maze.validate().is_perfect());
+    }
+
+    #[test]
+    fn test_generators_make_perfect_hex_mazes() {
+        let generators: [fn(&mut Maze); 3] = [Maze::dfs_maze, Maze::prims_maze, Maze::kruskals_maze];
+        for generate in generators {
+            let mut maze = Maze::with_topology(13, 9, Topology::Hex);
+            generate(&mut maze);
+            let validation = maze.validate();
+            assert!(validation.is_perfect(), "{}", validation);
+            // Passages leave through the west and north-west walls too
+            assert!(maze.grid.iter().flatten().any(|node| !node.walls[4] || !node.walls[5]));
+        }
+    }
+
+    #[test]
+    fn test_braid_opens_every_interior_wall() {
+        let mut maze = Maze::new(10, 10);
+        maze.braid(1.0);
+
+        for y in 0..10 {
+            for x in 0..10 {
+                let edges = [x == 0, x == 9, y == 0, y == 9].iter().filter(|&&edge| edge).count();
+                assert_eq!(maze.open_neighbors(x, y).len(), 4 - edges);
+            }
+        }
+    }
+
 
 }
 
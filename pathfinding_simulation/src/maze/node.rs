@@ -1,8 +1,8 @@
-
+use super::MAX_WALLS;
 
 pub struct Node {
     pub x: usize, 
     pub y: usize, 
     pub visited: bool, 
-    pub walls: [bool; 4],
+    pub walls: [bool; MAX_WALLS], // One per side of the cell, numbered as in `Topology`; only the first `wall_count()` are used
 }
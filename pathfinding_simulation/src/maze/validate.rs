@@ -0,0 +1,136 @@
+use std::fmt;
+
+use super::Maze;
+
+// Every node keeps its own copy of the walls it shares, so the two copies can
+// disagree. A sound maze has them agree, keeps its outer walls up and, to be
+// perfect, lets every node be reached by exactly one path.
+
+#[derive(Clone, Debug, Default)]
+pub struct Validation {
+    pub asymmetric_walls: Vec<(usize, usize, usize)>, // (x, y, wall) open on this side, closed on the other
+    pub open_boundary: Vec<(usize, usize, usize)>,    // (x, y, wall) open onto the outside of the grid
+    pub regions: usize,                               // Groups of cells that can reach each other
+    pub cycles: usize,                                // Independent loops: passages - cells + regions
+}
+
+impl Validation {
+    /// Every wall agrees with its neighbour and the outside is sealed.
+    pub fn is_consistent(&self) -> bool {
+        self.asymmetric_walls.is_empty() && self.open_boundary.is_empty()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.regions == 1
+    }
+
+    pub fn is_perfect(&self) -> bool {
+        self.is_consistent() && self.is_connected() && self.cycles == 0
+    }
+}
+
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_perfect() {
+            return write!(f, "perfect");
+        }
+        let mut problems = Vec::new();
+        if !self.asymmetric_walls.is_empty() {
+            problems.push(format!("{} one-sided walls", self.asymmetric_walls.len()));
+        }
+        if !self.open_boundary.is_empty() {
+            problems.push(format!("{} open boundary walls", self.open_boundary.len()));
+        }
+        if !self.is_connected() {
+            problems.push(format!("{} regions", self.regions));
+        }
+        if self.cycles > 0 {
+            problems.push(format!("{} loops", self.cycles));
+        }
+        write!(f, "{}", problems.join(", "))
+    }
+}
+
+impl Maze {
+    /// Checks the walls of every node, then floods the passages that are open
+    /// from both sides to count regions and loops.
+    pub fn validate(&self) -> Validation {
+        let mut validation = Validation::default();
+        let mut passages = 0;
+
+        for node in self.grid.iter().flatten() {
+            let (x, y) = (node.x, node.y);
+            for (wall, &standing) in node.walls[..self.topology.wall_count()].iter().enumerate() {
+                match self.neighbor((x, y), wall) {
+                    None if !standing => validation.open_boundary.push((x, y, wall)),
+                    None => {}
+                    Some((nx, ny)) => {
+                        let behind = self.grid[ny][nx].walls[self.topology.opposite(wall)];
+                        if !standing && behind {
+                            validation.asymmetric_walls.push((x, y, wall));
+                        } else if !standing && (ny, nx) > (y, x) {
+                            passages += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut seen = vec![vec![false; self.width]; self.height];
+        for node in self.grid.iter().flatten() {
+            if seen[node.y][node.x] {
+                continue;
+            }
+            validation.regions += 1;
+            seen[node.y][node.x] = true;
+            let mut stack = vec![(node.x, node.y)];
+            while let Some((x, y)) = stack.pop() {
+                for wall in 0..self.topology.wall_count() {
+                    let Some((nx, ny)) = self.passage((x, y), wall) else {
+                        continue;
+                    };
+                    let open_behind = !self.grid[ny][nx].walls[self.topology.opposite(wall)];
+                    if open_behind && !seen[ny][nx] {
+                        seen[ny][nx] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+        }
+
+        let cells = self.width * self.height;
+        validation.cycles = (passages + validation.regions).saturating_sub(cells);
+        validation
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braided_maze_has_loops() {
+        let mut maze = Maze::new(10, 10);
+        maze.braid(1.0);
+
+        let validation = maze.validate();
+        assert!(validation.is_consistent() && validation.is_connected());
+        // A full grid has one loop per interior corner
+        assert_eq!(validation.cycles, 9 * 9);
+        assert!(!validation.is_perfect());
+    }
+
+    #[test]
+    fn test_broken_walls_are_reported() {
+        let mut maze = Maze::new(3, 3);
+        maze.grid[1][1].walls[1] = false; // The neighbour's left wall still stands
+        maze.grid[0][2].walls[0] = false;
+
+        let validation = maze.validate();
+        assert_eq!(validation.asymmetric_walls, vec![(1, 1, 1)]);
+        assert_eq!(validation.open_boundary, vec![(2, 0, 0)]);
+        assert_eq!(validation.regions, 9);
+        assert_eq!(validation.to_string(), "1 one-sided walls, 1 open boundary walls, 9 regions");
+    }
+}
@@ -1,7 +1,7 @@
 use image::{RgbImage, Rgb};
 #[cfg(not(test))]
 use std::time::Duration;
-use crate::maze::Maze; // Import the Maze struct from the maze module
+use crate::maze::{Maze, Topology}; // Import the Maze struct from the maze module
 
 #[cfg(not(test))]
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
@@ -9,8 +9,16 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 #[cfg(test)]
 use indicatif::ProgressBar;
 
+/// Pixels across a cell. Hex walls are drawn at an angle and need more room to read.
+fn cell_size_for(maze: &Maze) -> u32 {
+    match maze.topology {
+        Topology::Square => 5,
+        Topology::Hex => 12,
+    }
+}
+
 pub fn save_maze_image(maze: &Maze, filename: &str) -> Result<(), std::io::Error> {
-    let cell_size = 5;
+    let cell_size = cell_size_for(maze);
 
     let wall_thickness = 1;
 
@@ -61,6 +69,10 @@ pub fn generate_maze_image_with_bar(
     wall_thickness: u32,
     bar: Option<&ProgressBar>, // Use Option to include or exclude the ProgressBar
 ) -> RgbImage {
+    if maze.topology == Topology::Hex {
+        return generate_hex_maze_image(maze, cell_size, bar);
+    }
+
     let img_width = maze.width as u32 * cell_size;
     let img_height = maze.height as u32 * cell_size;
     let mut img = RgbImage::new(img_width, img_height);
@@ -111,3 +123,99 @@ pub fn generate_maze_image_with_bar(
 
     img
 }
+
+// Hex cells are drawn as regular hexagons `cell_size` pixels across, pointy
+// side up, so each wall is one side of the hexagon around the cell's centre.
+
+fn hex_radius(cell_size: u32) -> f64 {
+    cell_size as f64 / 3f64.sqrt()
+}
+
+/// Pixel position of the centre of a hex cell.
+fn hex_center(cell: (usize, usize), cell_size: u32) -> (f64, f64) {
+    let (x, y) = Topology::Hex.center(cell);
+    let size = cell_size as f64;
+    (x * size + size / 2.0, y * size + hex_radius(cell_size))
+}
+
+/// Corner `i` of a hex cell, counted clockwise from the top. Wall n runs from corner n to corner n + 1.
+fn hex_corner(center: (f64, f64), cell_size: u32, i: usize) -> (f64, f64) {
+    let angle = (60.0 * i as f64 - 90.0).to_radians();
+    let radius = hex_radius(cell_size);
+    (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+}
+
+fn generate_hex_maze_image(maze: &Maze, cell_size: u32, bar: Option<&ProgressBar>) -> RgbImage {
+    // Odd rows stick out half a cell on the right, and the rows overlap by a quarter of a hexagon
+    let img_width = ((maze.width as f64 + 0.5) * cell_size as f64).ceil() as u32 + 1;
+    let bottom = hex_center((0, maze.height.saturating_sub(1)), cell_size).1 + hex_radius(cell_size);
+    let img_height = bottom.ceil() as u32 + 1;
+    let mut img = RgbImage::from_pixel(img_width, img_height, Rgb([255, 255, 255]));
+
+    let wall_color = Rgb([0, 0, 0]);
+    for row in &maze.grid {
+        for node in row {
+            let center = hex_center((node.x, node.y), cell_size);
+            for (wall, &standing) in node.walls[..maze.topology.wall_count()].iter().enumerate() {
+                if standing {
+                    let from = hex_corner(center, cell_size, wall);
+                    let to = hex_corner(center, cell_size, wall + 1);
+                    draw_line(&mut img, from, to, wall_color);
+                }
+            }
+
+            if let Some(bar) = bar {
+                bar.inc(1);
+            }
+        }
+    }
+
+    img
+}
+
+/// Draws a one pixel line, clipped to the image.
+fn draw_line(img: &mut RgbImage, from: (f64, f64), to: (f64, f64), color: Rgb<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as u32;
+    for step in 0..=steps {
+        let t = step as f64 / steps as f64;
+        let x = (from.0 + (to.0 - from.0) * t).round();
+        let y = (from.1 + (to.1 - from.1) * t).round();
+        if x >= 0.0 && y >= 0.0 && (x as u32) < img.width() && (y as u32) < img.height() {
+            img.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+/// Saves the maze with a solution path drawn over it.
+pub fn save_solution_image(maze: &Maze, path: &[(usize, usize)], filename: &str) -> Result<(), std::io::Error> {
+    let cell_size = cell_size_for(maze);
+    let mut img = generate_maze_image_with_bar(maze, cell_size, 1, None);
+
+    let path_color = Rgb([255, 0, 0]);
+    if maze.topology == Topology::Hex {
+        // A dot in the middle of each cell, well clear of the slanted walls
+        let reach = hex_radius(cell_size) / 2.0;
+        for &cell in path {
+            let (cx, cy) = hex_center(cell, cell_size);
+            for px in (cx - reach).floor() as u32..=(cx + reach).ceil() as u32 {
+                for py in (cy - reach).floor() as u32..=(cy + reach).ceil() as u32 {
+                    if (px as f64 - cx).hypot(py as f64 - cy) <= reach {
+                        img.put_pixel(px, py, path_color);
+                    }
+                }
+            }
+        }
+        return img.save(filename).map_err(std::io::Error::other);
+    }
+
+    for &(x, y) in path {
+        // Fill the inside of the cell, leaving its walls visible
+        for i in 1..cell_size - 1 {
+            for j in 1..cell_size - 1 {
+                img.put_pixel(x as u32 * cell_size + i, y as u32 * cell_size + j, path_color);
+            }
+        }
+    }
+
+    img.save(filename).map_err(std::io::Error::other)
+}
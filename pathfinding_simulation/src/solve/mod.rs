@@ -0,0 +1,150 @@
+use pathfinding_simulation_core::{DeadEndFilling, Passages, WalkStep, Walker, WalkerRule};
+
+use crate::maze::{Maze, Topology};
+
+// Maze solving strategies a person could follow from inside the maze. The
+// walkers only ever look at the walls of the cell they stand in, dead-end
+// filling looks at the whole maze at once. Both are shared with the GUI and
+// run here to the end in one go.
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Strategy {
+    LeftWallFollower,
+    RightWallFollower,
+    Pledge,
+    Tremaux,
+    DeadEndFilling,
+}
+
+pub struct Solution {
+    pub path: Option<Vec<(usize, usize)>>,
+    pub moves: usize,  // Cells walked, or cells filled for dead-end filling
+    pub looped: bool,  // The walker was caught walking in circles
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 5] = [
+        Strategy::LeftWallFollower,
+        Strategy::RightWallFollower,
+        Strategy::Pledge,
+        Strategy::Tremaux,
+        Strategy::DeadEndFilling,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::LeftWallFollower => "Left-hand wall follower",
+            Strategy::RightWallFollower => "Right-hand wall follower",
+            Strategy::Pledge => "Pledge",
+            Strategy::Tremaux => "Tremaux",
+            Strategy::DeadEndFilling => "Dead-end filling",
+        }
+    }
+}
+
+impl Passages for Maze {
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn passage(&self, cell: (usize, usize), wall: usize) -> Option<(usize, usize)> {
+        Maze::passage(self, cell, wall)
+    }
+}
+
+pub fn solve(maze: &Maze, strategy: Strategy, start: (usize, usize), goal: (usize, usize)) -> Solution {
+    let rule = match strategy {
+        Strategy::LeftWallFollower => WalkerRule::LeftWallFollower,
+        Strategy::RightWallFollower => WalkerRule::RightWallFollower,
+        Strategy::Pledge => WalkerRule::Pledge,
+        Strategy::Tremaux => WalkerRule::Tremaux,
+        Strategy::DeadEndFilling => {
+            let mut filling = DeadEndFilling::new(maze, start, goal);
+            while filling.step(maze).is_some() {}
+            return Solution { path: filling.path(maze), moves: filling.filled_count, looped: false };
+        }
+    };
+
+    let mut walker = Walker::new(maze, rule, start, goal);
+    loop {
+        let (path, looped) = match walker.step(maze) {
+            WalkStep::Moved { .. } | WalkStep::Turned => continue,
+            WalkStep::Arrived => (Some(walker.route().to_vec()), false),
+            WalkStep::Looped => (None, true),
+            WalkStep::Stuck => (None, false),
+        };
+        return Solution { path, moves: walker.moves, looped };
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    fn shortest_path_len(maze: &Maze, start: (usize, usize), goal: (usize, usize)) -> Option<usize> {
+        let mut distance = vec![vec![None; maze.width]; maze.height];
+        let mut queue = VecDeque::from([start]);
+        distance[start.1][start.0] = Some(0);
+        while let Some((x, y)) = queue.pop_front() {
+            for (nx, ny) in maze.open_neighbors(x, y) {
+                if distance[ny][nx].is_none() {
+                    distance[ny][nx] = Some(distance[y][x].unwrap() + 1);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        distance[goal.1][goal.0]
+    }
+
+    #[test]
+    fn test_strategies_solve_perfect_maze() {
+        let mut maze = Maze::new(15, 12);
+        maze.prims_maze();
+        let shortest = shortest_path_len(&maze, (0, 0), (14, 11));
+
+        for strategy in Strategy::ALL {
+            let solution = solve(&maze, strategy, (0, 0), (14, 11));
+            let path = solution.path.unwrap_or_else(|| panic!("{} found no path", strategy.name()));
+            assert_eq!(Some(path.len() - 1), shortest, "{}", strategy.name());
+            assert!(!solution.looped);
+        }
+    }
+
+    #[test]
+    fn test_strategies_solve_hex_maze() {
+        let mut maze = Maze::with_topology(13, 10, Topology::Hex);
+        maze.kruskals_maze();
+        let shortest = shortest_path_len(&maze, (0, 0), (12, 9));
+
+        for strategy in Strategy::ALL {
+            let solution = solve(&maze, strategy, (0, 0), (12, 9));
+            let path = solution.path.unwrap_or_else(|| panic!("{} found no path", strategy.name()));
+            assert_eq!(Some(path.len() - 1), shortest, "{}", strategy.name());
+            assert!(path.windows(2).all(|step| maze.topology.distance(step[0], step[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn test_wall_follower_loops_in_open_room() {
+        let mut maze = Maze::new(4, 4);
+        maze.braid(1.0);
+
+        let solution = solve(&maze, Strategy::RightWallFollower, (1, 1), (3, 3));
+        assert!(solution.looped);
+        assert!(solution.path.is_none());
+
+        let solution = solve(&maze, Strategy::Tremaux, (1, 1), (3, 3));
+        assert!(solution.path.is_some());
+    }
+}
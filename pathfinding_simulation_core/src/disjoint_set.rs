@@ -0,0 +1,104 @@
+// Union-find over the cells of a maze, numbered y * width + x. The smaller set
+// always goes under the larger one's root and every find halves the path it
+// walks, so the trees stay a few levels deep and no recursion is needed even
+// on very large grids.
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>, // Number of members, only kept up to date for roots
+}
+
+impl DisjointSet {
+    /// Every element starts in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// The root that represents the set `element` belongs to.
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            // Point at the grandparent, halving the path for later finds
+            self.parent[element] = self.parent[self.parent[element]];
+            element = self.parent[element];
+        }
+        element
+    }
+
+    /// Like `find`, without shortening the path, for when the sets can't be changed.
+    pub fn root(&self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            element = self.parent[element];
+        }
+        element
+    }
+
+    /// Merges the sets of `a` and `b`. Returns false if they already were one set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut root_a, mut root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return false;
+        }
+        if self.size[root_a] < self.size[root_b] {
+            (root_a, root_b) = (root_b, root_a);
+        }
+        self.parent[root_b] = root_a;
+        self.size[root_a] += self.size[root_b];
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set `element` belongs to.
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    /// Number of separate sets.
+    pub fn count(&self) -> usize {
+        (0..self.parent.len()).filter(|&element| self.parent[element] == element).count()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_and_find() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.count(), 3);
+    }
+
+    #[test]
+    fn test_long_chain_stays_shallow() {
+        // Joining every element onto the chain one by one used to build a path
+        // as long as the chain, deep enough to overflow a recursive find
+        let len = 1_000_000;
+        let mut sets = DisjointSet::new(len);
+        for element in 1..len {
+            sets.union(element, element - 1);
+        }
+        assert_eq!(sets.set_size(0), len);
+        assert_eq!(sets.count(), 1);
+        // Union by size keeps everything directly under the first root
+        assert!((0..len).all(|element| sets.parent[sets.parent[element]] == sets.parent[element]));
+    }
+}
@@ -0,0 +1,12 @@
+// lib.rs
+//
+// The pieces of a maze that don't depend on how its cells are stored, shared
+// by the terminal version and the GUI.
+
+mod disjoint_set;
+mod topology;
+mod walker;
+
+pub use disjoint_set::DisjointSet;
+pub use topology::{Topology, MAX_WALLS};
+pub use walker::{DeadEndFilling, Fill, Passages, WalkStep, Walker, WalkerRule};
@@ -0,0 +1,116 @@
+use std::fmt;
+
+// The shape of the cells and which cells are neighbours. Square cells number
+// their walls 0 up, 1 right, 2 down, 3 left. Hex cells are pointy topped, with
+// every odd row pushed half a cell to the right, and number theirs clockwise
+// from the upper right: 0 north-east, 1 east, 2 south-east, 3 south-west,
+// 4 west, 5 north-west. Either way the wall across from wall n is n plus half
+// the wall count, and n + 1 is one turn clockwise from n.
+
+/// Most walls a cell can have, for per-wall state that has to fit any topology.
+pub const MAX_WALLS: usize = 6;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    #[default]
+    Square,
+    Hex,
+}
+
+impl Topology {
+    pub const ALL: [Topology; 2] = [Topology::Square, Topology::Hex];
+
+    pub fn wall_count(self) -> usize {
+        match self {
+            Topology::Square => 4,
+            Topology::Hex => 6,
+        }
+    }
+
+    /// The same wall seen from the cell on the other side of it.
+    pub fn opposite(self, wall: usize) -> usize {
+        (wall + self.wall_count() / 2) % self.wall_count()
+    }
+
+    /// How far the cell beyond `wall` is from a cell in row `y`, in columns and rows.
+    pub fn offset(self, y: usize, wall: usize) -> (isize, isize) {
+        match self {
+            Topology::Square => [(0, -1), (1, 0), (0, 1), (-1, 0)][wall],
+            Topology::Hex => {
+                // Moving diagonally shifts the column only half the time
+                let shift = (y % 2) as isize;
+                [(shift, -1), (1, 0), (shift, 1), (shift - 1, 1), (-1, 0), (shift - 1, -1)][wall]
+            }
+        }
+    }
+
+    /// Unit vector from a cell's centre through the middle of `wall`, with y pointing down.
+    pub fn direction(self, wall: usize) -> (f64, f64) {
+        let half = 3f64.sqrt() / 2.0;
+        match self {
+            Topology::Square => [(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)][wall],
+            Topology::Hex => [(0.5, -half), (1.0, 0.0), (0.5, half), (-0.5, half), (-1.0, 0.0), (-0.5, -half)][wall],
+        }
+    }
+
+    /// Where a cell's centre is, measured so that neighbouring centres are one apart.
+    pub fn center(self, (x, y): (usize, usize)) -> (f64, f64) {
+        match self {
+            Topology::Square => (x as f64, y as f64),
+            Topology::Hex => (x as f64 + (y % 2) as f64 * 0.5, y as f64 * 3f64.sqrt() / 2.0),
+        }
+    }
+
+    /// Fewest steps between two cells with no walls in the way.
+    pub fn distance(self, from: (usize, usize), to: (usize, usize)) -> usize {
+        match self {
+            Topology::Square => from.0.abs_diff(to.0) + from.1.abs_diff(to.1),
+            Topology::Hex => {
+                // In axial coordinates a step changes q, r or q + r by one, and two of them at once
+                let axial = |(x, y): (usize, usize)| (x as isize - (y / 2) as isize, y as isize);
+                let ((q1, r1), (q2, r2)) = (axial(from), axial(to));
+                let (dq, dr) = (q1 - q2, r1 - r2);
+                ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize
+            }
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Topology::Square => "Square",
+            Topology::Hex => "Hexagonal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_steps_are_one_apart() {
+        let topology = Topology::Hex;
+        for y in 1..3 {
+            for wall in 0..6 {
+                let (dx, dy) = topology.offset(y, wall);
+                let neighbor = ((2 + dx) as usize, (y as isize + dy) as usize);
+                assert_eq!(topology.distance((2, y), neighbor), 1);
+
+                // Coming back through the opposite wall leads home
+                let (back_x, back_y) = topology.offset(neighbor.1, topology.opposite(wall));
+                assert_eq!((neighbor.0 as isize + back_x, neighbor.1 as isize + back_y), (2, y as isize));
+
+                // The wall faces the neighbour's centre
+                let ((x1, y1), (x2, y2)) = (topology.center((2, y)), topology.center(neighbor));
+                let (wx, wy) = topology.direction(wall);
+                assert!((x1 + wx - x2).abs() < 1e-9 && (y1 + wy - y2).abs() < 1e-9);
+            }
+        }
+        assert_eq!(topology.distance((0, 0), (4, 3)), 6);
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::topology::{Topology, MAX_WALLS};
+
+// Strategies a person could follow standing inside the maze. Instead of
+// growing a frontier, a walker moves one cell per step and only knows the
+// walls around it. Dead-end filling is the odd one out: it looks at the
+// whole maze at once and fills in corridors until only the solution is left.
+// Cells are numbered y * width + x inside, and handed out as (x, y).
+
+/// Headings use the same numbering as the walls, and turns are counted in
+/// sides of the cell: a quarter turn on square cells, a sixth on hex cells.
+/// Turning back is half the sides and turning left all but one.
+const TURN_RIGHT: usize = 1;
+
+/// What the walkers need to know about a maze, whichever way its cells are stored.
+pub trait Passages {
+    fn topology(&self) -> Topology;
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    /// The cell reached by leaving `cell` through `wall`, or None if that
+    /// wall is standing or leads off the grid.
+    fn passage(&self, cell: (usize, usize), wall: usize) -> Option<(usize, usize)>;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WalkerRule {
+    LeftWallFollower,
+    RightWallFollower,
+    Pledge,
+    Tremaux,
+}
+
+/// What a walker did with one step.
+#[derive(PartialEq, Debug)]
+pub enum WalkStep {
+    Moved { cut: Vec<(usize, usize)> }, // Cells dropped from the route because the walker closed a loop
+    Turned,                             // Pledge turned on the spot to put the obstacle beside it
+    Arrived,
+    Looped, // Caught walking in circles, the rest of the walk would repeat forever
+    Stuck,  // Walled in, or Trémaux has walked every passage twice
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Walker {
+    rule: WalkerRule,
+    width: usize,
+    goal: (usize, usize),
+    pub position: (usize, usize),
+    heading: usize,
+    entry: Option<usize>,                // Wall of the current cell the walker came in through
+    preferred: usize,                    // Pledge: heading the walker tries to keep
+    blocked_turn: usize,                 // Pledge: which way to turn when the preferred heading is walled off
+    turns: i32,                          // Pledge: net turns, in sides of the cell, made while following a wall
+    passage_marks: Vec<[u8; MAX_WALLS]>, // Trémaux: times each passage has been walked, per cell and wall
+    marked_passages: usize,              // Trémaux: passages carrying at least one mark
+    seen: HashSet<(usize, usize, i32)>,  // (cell, heading, turns) states, a repeat means the walker loops
+    route: Vec<(usize, usize)>,          // Cells walked so far with any loops cut out
+    route_index: Vec<Option<usize>>,     // Position of each cell in `route`
+    pub moves: usize,
+    pub looped: bool,
+}
+
+impl Walker {
+    pub fn new(maze: &impl Passages, rule: WalkerRule, start: (usize, usize), goal: (usize, usize)) -> Self {
+        // Head for the goal through whichever wall faces it most directly
+        let topology = maze.topology();
+        let sides = topology.wall_count();
+        let ((start_x, start_y), (goal_x, goal_y)) = (topology.center(start), topology.center(goal));
+        let towards_goal = |wall: usize| {
+            let (dx, dy) = topology.direction(wall);
+            dx * (goal_x - start_x) + dy * (goal_y - start_y)
+        };
+        let preferred = (0..sides)
+            .reduce(|best, wall| if towards_goal(wall) > towards_goal(best) { wall } else { best })
+            .unwrap_or(0);
+        // Sliding along an obstacle should carry the walker towards the goal, so
+        // turn to whichever side of the preferred heading also faces it
+        let turn_left = sides - TURN_RIGHT;
+        let (right, left) = ((preferred + TURN_RIGHT) % sides, (preferred + turn_left) % sides);
+        let blocked_turn = if towards_goal(right) >= towards_goal(left) { TURN_RIGHT } else { turn_left };
+
+        let width = maze.width();
+        let cells = width * maze.height();
+        let mut route_index = vec![None; cells];
+        route_index[start.1 * width + start.0] = Some(0);
+        let passage_marks = if rule == WalkerRule::Tremaux { vec![[0; MAX_WALLS]; cells] } else { Vec::new() };
+
+        Walker {
+            rule,
+            width,
+            goal,
+            position: start,
+            heading: preferred,
+            entry: None,
+            preferred,
+            blocked_turn,
+            turns: 0,
+            passage_marks,
+            marked_passages: 0,
+            seen: HashSet::new(),
+            route: vec![start],
+            route_index,
+            moves: 0,
+            looped: false,
+        }
+    }
+
+    /// Cells walked from the start to where the walker stands, with any loops cut out.
+    pub fn route(&self) -> &[(usize, usize)] {
+        &self.route
+    }
+
+    /// Passages Trémaux has marked so far, which is all the memory it needs.
+    pub fn marked_passages(&self) -> usize {
+        self.marked_passages
+    }
+
+    /// Moves the walker one cell, or turns it on the spot.
+    pub fn step(&mut self, maze: &impl Passages) -> WalkStep {
+        if self.position == self.goal {
+            return WalkStep::Arrived;
+        }
+
+        let sides = maze.topology().wall_count();
+        let direction = match self.rule {
+            WalkerRule::Tremaux => self.tremaux_direction(maze),
+            rule => {
+                // The Pledge turn counter is unbounded, so also give up after a generous number of moves
+                let limit = 8 * maze.width() * maze.height();
+                let (x, y) = self.position;
+                if !self.seen.insert((y * self.width + x, self.heading, self.turns)) || self.moves > limit {
+                    self.looped = true;
+                    return WalkStep::Looped;
+                }
+
+                if rule == WalkerRule::Pledge && self.turns == 0 {
+                    if maze.passage(self.position, self.preferred).is_some() {
+                        Some(self.preferred)
+                    } else {
+                        // Blocked: turn so the obstacle is beside the walker, then follow it
+                        self.heading = (self.preferred + self.blocked_turn) % sides;
+                        self.turns = if self.blocked_turn == TURN_RIGHT { 1 } else { -1 };
+                        return WalkStep::Turned;
+                    }
+                } else {
+                    // Pledge keeps a hand on the obstacle, on the side opposite its first turn
+                    let right_hand = match rule {
+                        WalkerRule::LeftWallFollower => false,
+                        WalkerRule::RightWallFollower => true,
+                        _ => self.blocked_turn != TURN_RIGHT,
+                    };
+                    let turn = hand_on_wall(sides, right_hand)
+                        .find(|&turn| maze.passage(self.position, (self.heading + turn) % sides).is_some());
+                    if let (WalkerRule::Pledge, Some(turn)) = (rule, turn) {
+                        let back = (sides / 2) as i32;
+                        self.turns += match turn as i32 {
+                            // Turning around with the left hand on the wall bends away from it
+                            turn if turn == back && !right_hand => back,
+                            turn if turn < back => turn,
+                            turn => turn - sides as i32,
+                        };
+                    }
+                    turn.map(|turn| (self.heading + turn) % sides)
+                }
+            }
+        };
+
+        match direction {
+            Some(direction) => self.walk(maze, direction),
+            None => WalkStep::Stuck,
+        }
+    }
+
+    /// Picks the next passage by Trémaux's rules and marks it.
+    fn tremaux_direction(&mut self, maze: &impl Passages) -> Option<usize> {
+        let topology = maze.topology();
+        let open: Vec<usize> = (0..topology.wall_count())
+            .filter(|&wall| maze.passage(self.position, wall).is_some())
+            .collect();
+        let current = self.position.1 * self.width + self.position.0;
+        let marks = self.passage_marks[current];
+        let others: Vec<usize> = open.iter().copied().filter(|&wall| Some(wall) != self.entry).collect();
+
+        let direction = if !others.is_empty() && others.iter().all(|&wall| marks[wall] == 0) {
+            others[0] // A fresh junction: take any new passage
+        } else if let Some(entry) = self.entry.filter(|&entry| marks[entry] == 1) {
+            entry // Reached somewhere already visited (or a dead end), go back the way we came
+        } else {
+            open.into_iter()
+                .filter(|&wall| marks[wall] < 2)
+                .min_by_key(|&wall| marks[wall])?
+        };
+
+        let (nx, ny) = maze.passage(self.position, direction)?;
+        if marks[direction] == 0 {
+            self.marked_passages += 1;
+        }
+        self.passage_marks[current][direction] += 1;
+        self.passage_marks[ny * self.width + nx][topology.opposite(direction)] += 1;
+        Some(direction)
+    }
+
+    fn walk(&mut self, maze: &impl Passages, direction: usize) -> WalkStep {
+        let Some(next) = maze.passage(self.position, direction) else {
+            return WalkStep::Stuck;
+        };
+        self.position = next;
+        self.heading = direction;
+        self.entry = Some(maze.topology().opposite(direction));
+        self.moves += 1;
+
+        let cell = next.1 * self.width + next.0;
+        let mut cut = Vec::new();
+        match self.route_index[cell] {
+            Some(index) => {
+                // Walked in a circle back onto the route: cut the loop out
+                for (x, y) in self.route.drain(index + 1..) {
+                    self.route_index[y * self.width + x] = None;
+                    cut.push((x, y));
+                }
+            }
+            None => {
+                self.route_index[cell] = Some(self.route.len());
+                self.route.push(next);
+            }
+        }
+        WalkStep::Moved { cut }
+    }
+}
+
+/// Turns to try, in order, for a walker keeping a hand on the wall: sweep
+/// from the hand's side through straight ahead to the other side, and only
+/// turn back at a dead end.
+fn hand_on_wall(sides: usize, right_hand: bool) -> impl Iterator<Item = usize> {
+    let back = sides / 2;
+    (1..=sides).map(move |step| if right_hand { (back + sides - step) % sides } else { (back + step) % sides })
+}
+
+/// A dead end filled in, and the cells that became dead ends because of it.
+pub struct Fill {
+    pub cell: (usize, usize),
+    pub dead_ends: Vec<(usize, usize)>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeadEndFilling {
+    width: usize,
+    start: (usize, usize),
+    goal: (usize, usize),
+    dead_ends: Vec<(usize, usize)>, // Dead ends waiting to be filled
+    filled: Vec<bool>,
+    pub filled_count: usize,
+}
+
+impl DeadEndFilling {
+    pub fn new(maze: &impl Passages, start: (usize, usize), goal: (usize, usize)) -> Self {
+        let width = maze.width();
+        let mut filling = DeadEndFilling {
+            width,
+            start,
+            goal,
+            dead_ends: Vec::new(),
+            filled: vec![false; width * maze.height()],
+            filled_count: 0,
+        };
+        for y in 0..maze.height() {
+            for x in 0..width {
+                if filling.is_dead_end(maze, (x, y)) {
+                    filling.dead_ends.push((x, y));
+                }
+            }
+        }
+        filling
+    }
+
+    /// Dead ends found but not yet filled.
+    pub fn dead_ends(&self) -> &[(usize, usize)] {
+        &self.dead_ends
+    }
+
+    /// Fills one dead end, None once there are none left.
+    pub fn step(&mut self, maze: &impl Passages) -> Option<Fill> {
+        let cell = self.dead_ends.pop()?;
+        self.filled[cell.1 * self.width + cell.0] = true;
+        self.filled_count += 1;
+
+        let mut dead_ends = Vec::new();
+        for wall in 0..maze.topology().wall_count() {
+            let Some((nx, ny)) = maze.passage(cell, wall) else {
+                continue;
+            };
+            if !self.filled[ny * self.width + nx] && self.is_dead_end(maze, (nx, ny)) {
+                self.dead_ends.push((nx, ny));
+                dead_ends.push((nx, ny));
+            }
+        }
+        Some(Fill { cell, dead_ends })
+    }
+
+    /// Shortest route through the cells left unfilled. On a perfect maze that
+    /// is the only route left, on a braided one loops remain.
+    pub fn path(&self, maze: &impl Passages) -> Option<Vec<(usize, usize)>> {
+        let index = |(x, y): (usize, usize)| y * self.width + x;
+        let mut came_from = vec![None; self.filled.len()];
+        let mut queue = VecDeque::from([self.start]);
+        came_from[index(self.start)] = Some(self.start);
+
+        while let Some(cell) = queue.pop_front() {
+            if cell == self.goal {
+                let mut path = vec![cell];
+                let mut current = cell;
+                while current != self.start {
+                    current = came_from[index(current)]?;
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for wall in 0..maze.topology().wall_count() {
+                let Some(next) = maze.passage(cell, wall) else {
+                    continue;
+                };
+                if !self.filled[index(next)] && came_from[index(next)].is_none() {
+                    came_from[index(next)] = Some(cell);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// A cell other than the start or goal with exactly one unfilled way out.
+    fn is_dead_end(&self, maze: &impl Passages, cell: (usize, usize)) -> bool {
+        if cell == self.start || cell == self.goal {
+            return false;
+        }
+        let exits = (0..maze.topology().wall_count())
+            .filter_map(|wall| maze.passage(cell, wall))
+            .filter(|&(x, y)| !self.filled[y * self.width + x])
+            .count();
+        exits == 1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An open room, with any walls listed in `closed` standing on both sides.
+    struct Room {
+        topology: Topology,
+        width: usize,
+        height: usize,
+        closed: HashSet<((usize, usize), usize)>,
+    }
+
+    impl Room {
+        fn new(topology: Topology, width: usize, height: usize) -> Self {
+            Room { topology, width, height, closed: HashSet::new() }
+        }
+
+        fn neighbor(&self, (x, y): (usize, usize), wall: usize) -> Option<(usize, usize)> {
+            let (dx, dy) = self.topology.offset(y, wall);
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            let inside = (0..self.width as isize).contains(&nx) && (0..self.height as isize).contains(&ny);
+            inside.then_some((nx as usize, ny as usize))
+        }
+
+        /// Closes every wall around `cell`, leaving a pillar to walk around.
+        fn pillar(&mut self, cell: (usize, usize)) {
+            for wall in 0..self.topology.wall_count() {
+                if let Some(next) = self.neighbor(cell, wall) {
+                    self.closed.insert((cell, wall));
+                    self.closed.insert((next, self.topology.opposite(wall)));
+                }
+            }
+        }
+    }
+
+    impl Passages for Room {
+        fn topology(&self) -> Topology {
+            self.topology
+        }
+        fn width(&self) -> usize {
+            self.width
+        }
+        fn height(&self) -> usize {
+            self.height
+        }
+        fn passage(&self, cell: (usize, usize), wall: usize) -> Option<(usize, usize)> {
+            if self.closed.contains(&(cell, wall)) {
+                return None;
+            }
+            self.neighbor(cell, wall)
+        }
+    }
+
+    fn walk(room: &Room, rule: WalkerRule, start: (usize, usize), goal: (usize, usize)) -> (Walker, WalkStep) {
+        let mut walker = Walker::new(room, rule, start, goal);
+        loop {
+            match walker.step(room) {
+                WalkStep::Moved { .. } | WalkStep::Turned => {}
+                outcome => return (walker, outcome),
+            }
+        }
+    }
+
+    #[test]
+    fn test_wall_follower_loops_in_open_room() {
+        // Away from the walls a wall follower never touches one and circles on the spot
+        let room = Room::new(Topology::Square, 4, 4);
+        let (walker, outcome) = walk(&room, WalkerRule::RightWallFollower, (1, 1), (3, 3));
+        assert_eq!(outcome, WalkStep::Looped);
+        assert!(walker.looped);
+
+        // Trémaux remembers where it has been and gets there
+        let (walker, outcome) = walk(&room, WalkerRule::Tremaux, (1, 1), (3, 3));
+        assert_eq!(outcome, WalkStep::Arrived);
+        assert_eq!(walker.route().last(), Some(&(3, 3)));
+        assert!(walker.marked_passages() > 0);
+    }
+
+    #[test]
+    fn test_pledge_walks_around_pillars_either_way() {
+        // Whichever hand ends up on the pillar, the turn count brings Pledge back
+        // on course. A goal just north of the row makes it slide round the top.
+        let mut room = Room::new(Topology::Square, 7, 7);
+        room.pillar((3, 3));
+        for (start, goal) in [((0, 3), (6, 3)), ((0, 3), (6, 2)), ((6, 3), (0, 3)), ((3, 0), (3, 6))] {
+            let (walker, outcome) = walk(&room, WalkerRule::Pledge, start, goal);
+            assert_eq!(outcome, WalkStep::Arrived, "from {:?} to {:?}", start, goal);
+            let route = walker.route();
+            assert_eq!((route.first(), route.last()), (Some(&start), Some(&goal)));
+            assert!(route.windows(2).all(|step| Topology::Square.distance(step[0], step[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn test_dead_end_filling_leaves_the_corridor() {
+        // A corridor along the top row with a branch hanging off the middle of it
+        let mut room = Room::new(Topology::Square, 5, 3);
+        for (x, y) in [(0, 1), (1, 1), (3, 1), (4, 1), (0, 2), (1, 2), (3, 2), (4, 2)] {
+            room.pillar((x, y));
+        }
+        let mut filling = DeadEndFilling::new(&room, (0, 0), (4, 0));
+        assert_eq!(filling.dead_ends(), &[(2, 2)]);
+
+        let mut filled = Vec::new();
+        while let Some(fill) = filling.step(&room) {
+            filled.push(fill.cell);
+        }
+        assert_eq!(filled, [(2, 2), (2, 1)]);
+        assert_eq!(filling.path(&room), Some((0..5).map(|x| (x, 0)).collect()));
+    }
+}
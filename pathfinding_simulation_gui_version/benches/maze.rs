@@ -0,0 +1,109 @@
+// Benchmarks for the hot paths: Maze::step while generating, the pathfinders,
+// and the per-frame rebuild of a window's maze texture.
+//
+// cargo bench
+// cargo bench -- generation/Prims      (only what matches the filter)
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use egui::{Color32, ColorImage};
+use pathfinding_simulation_with_gui::{Maze, MazeAlgorithms, PathfindingAlgorithms, SolverOptions, WindowState};
+
+const SIZES: [usize; 3] = [25, 50, 100];
+
+/// Steps per call, as many as the fastest playback speed.
+const STEPS: usize = 1000;
+
+fn new_generator(algorithm: MazeAlgorithms, size: usize) -> Maze {
+    let mut maze = Maze::new(size, size);
+    maze.seed(1);
+    maze.init_generator(algorithm);
+    maze
+}
+
+fn generate(mut maze: Maze) -> Maze {
+    let mut generation_time = Duration::ZERO;
+    while maze.step(STEPS, &mut generation_time) {}
+    maze
+}
+
+/// A finished maze with a few loops, so the pathfinders have choices to make.
+fn braided_maze(size: usize) -> Maze {
+    let mut maze = generate(new_generator(MazeAlgorithms::Prims, size));
+    maze.braid(0.1);
+    maze
+}
+
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    group.sample_size(10); // Aldous-Broder takes a while on the larger grids
+    for algorithm in MazeAlgorithms::ALL {
+        for size in SIZES {
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", algorithm), size), &size, |b, &size| {
+                b.iter_batched(|| new_generator(algorithm, size), generate, BatchSize::SmallInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Prim's on growing grids, reported per cell. With constant time removal
+/// from the frontier the time per cell should stay roughly flat.
+fn prims_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prims_scaling");
+    group.sample_size(10);
+    for size in [100, 200, 400, 800] {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(|| new_generator(MazeAlgorithms::Prims, size), generate, BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+fn solving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solving");
+    for size in [25, 50] {
+        let maze = braided_maze(size);
+        for algorithm in PathfindingAlgorithms::ALL {
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", algorithm), size), &maze, |b, maze| {
+                b.iter_batched(
+                    || {
+                        let mut maze = maze.clone();
+                        maze.init_solver(algorithm, SolverOptions::default(), (0, 0), (size - 1, size - 1));
+                        maze
+                    },
+                    |mut maze| {
+                        let mut solve_time = Duration::ZERO;
+                        while maze.solve_step(STEPS, &mut solve_time) {}
+                        maze
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render_maze_to_image");
+    for size in SIZES {
+        let mut maze = braided_maze(size);
+        maze.init_solver(PathfindingAlgorithms::Astar, SolverOptions::default(), (0, 0), (size - 1, size - 1));
+        let mut solve_time = Duration::ZERO;
+        while maze.solve_step(STEPS, &mut solve_time) {}
+        let window = WindowState::new(0, String::from("bench"), maze);
+
+        // The size the texture gets in a default window
+        let mut image = ColorImage::new([800, 800], Color32::WHITE);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| window.render_maze_to_image(&mut image))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation, prims_scaling, solving, rendering);
+criterion_main!(benches);
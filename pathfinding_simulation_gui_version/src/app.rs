@@ -1,16 +1,22 @@
 // app.rs
+#[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
+use web_time::Instant;
 
 
 mod algorithms;
 mod central_panel;
+mod frame_scheduler;
 mod maze;
+mod race;
 mod settings;
 mod side_panel;
 mod window_state;
 
-pub use algorithms::{MazeAlgorithms, PathfindingAlgorithms};
-pub use maze::Maze;
+pub use algorithms::{Heuristic, MazeAlgorithms, PathfindingAlgorithms};
+pub use frame_scheduler::FrameScheduler;
+pub use maze::{Maze, SolverOptions};
+pub use race::Race;
 pub use settings::AppSettings;
 pub use window_state::WindowState;
 
@@ -20,7 +26,8 @@ pub struct Main {
     windows: Vec<WindowState>,
     next_window_id: usize,
     pub selected_window_id: Option<usize>,
-    last_frame_fps: usize,
+    scheduler: FrameScheduler,
+    race: Option<Race>,
 }
 
 impl Default for Main {
@@ -30,14 +37,38 @@ impl Default for Main {
             windows: Vec::new(),
             next_window_id: 0,
             selected_window_id: None,
-            last_frame_fps: 0,
+            scheduler: FrameScheduler::default(),
+            race: None,
         }
     }
 }
 
+const SETTINGS_KEY: &str = "settings";
+const WINDOWS_KEY: &str = "windows";
+
 impl Main {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+
+        let Some(storage) = cc.storage else {
+            return app;
+        };
+
+        if let Some(settings) = eframe::get_value(storage, SETTINGS_KEY) {
+            app.settings = settings;
+        }
+
+        if app.settings.restore_session {
+            if let Some(windows) = eframe::get_value::<Vec<WindowState>>(storage, WINDOWS_KEY) {
+                app.windows = windows;
+            }
+            for window in &mut app.windows {
+                window.needs_redraw = true; // Textures are not persisted
+            }
+            app.next_window_id = app.windows.iter().map(|w| w.id + 1).max().unwrap_or(0);
+        }
+
+        app
     }
 }
 
@@ -89,23 +120,46 @@ impl eframe::App for Main {
 
         let any_maze_generating = std::sync::atomic::AtomicBool::new(false);
 
-        // Perform maze generation steps in parallel
-        self.windows
-            .par_iter_mut() // Use rayon's parallel iterator
-            .for_each(|window| {
-                if window.generating {
-                    // Perform a number of steps based on visualization speed
-                    let generation_continues =
-                        window.maze.step(self.settings.visualization_speed as usize, &mut window.generation_time);
-                    if !generation_continues {
-                        // Maze generation is complete
-                        window.generating = false;
-                    } else {
-                        any_maze_generating.store(true, std::sync::atomic::Ordering::Relaxed);
-                    }
-                    window.needs_redraw = true; // Maze has changed, needs to be redrawn
+        // Number of steps that fit in this frame's time budget
+        let steps = self
+            .scheduler
+            .begin_frame(self.settings.visualization_speed as usize, self.settings.desired_fps);
+        let stepping_start = Instant::now();
+
+        let step_window = |window: &mut WindowState| {
+            if window.is_playing() {
+                // Perform a number of steps based on visualization speed
+                let generation_continues = window.maze.step(steps, &mut window.generation_time);
+                if !generation_continues {
+                    // Maze generation is complete
+                    window.pause();
+                } else {
+                    any_maze_generating.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                window.needs_redraw = true; // Maze has changed, needs to be redrawn
+            }
+            if window.solving {
+                let solving_continues = window.maze.solve_step(steps, &mut window.solve_time);
+                if !solving_continues {
+                    window.solving = false;
+                } else {
+                    any_maze_generating.store(true, std::sync::atomic::Ordering::Relaxed);
                 }
-            });
+                window.needs_redraw = true;
+            }
+            window.update_flashing();
+        };
+
+        // Perform maze generation steps in parallel, browsers only get a single thread
+        #[cfg(not(target_arch = "wasm32"))]
+        self.windows.par_iter_mut().for_each(step_window); // Use rayon's parallel iterator
+        #[cfg(target_arch = "wasm32")]
+        self.windows.iter_mut().for_each(step_window);
+
+        let any_maze_generating = any_maze_generating.load(std::sync::atomic::Ordering::Relaxed);
+        if any_maze_generating {
+            self.scheduler.end_stepping(steps, stepping_start.elapsed());
+        }
 
         // Proceed with your normal UI code
         let integration_info = &frame.info();
@@ -113,11 +167,24 @@ impl eframe::App for Main {
         self.generate_side_panel(ctx, integration_info);
         self.generate_central_panel(ctx);
 
-        // Request a repaint if any window needs to be redrawn
-        if any_maze_generating.load(std::sync::atomic::Ordering::Relaxed)
-            || self.windows.iter().any(|w| w.needs_redraw)
-        {
+        // Request a repaint if any window needs to be redrawn, pacing
+        // running animations at the desired frame rate
+        if self.windows.iter().any(|w| w.needs_redraw) {
             ctx.request_repaint();
+        } else if any_maze_generating {
+            ctx.request_repaint_after(self.scheduler.time_until_next_frame(self.settings.desired_fps));
+        }
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings);
+
+        if self.settings.restore_session {
+            // Mazes still generating can't be resumed, their generator is not saved
+            let finished: Vec<&WindowState> = self.windows.iter().filter(|w| w.maze.is_generated()).collect();
+            eframe::set_value(storage, WINDOWS_KEY, &finished);
+        } else {
+            eframe::set_value::<Vec<WindowState>>(storage, WINDOWS_KEY, &Vec::new());
         }
     }
 }
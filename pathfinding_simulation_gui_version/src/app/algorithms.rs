@@ -1,6 +1,10 @@
 use std::fmt;
 
-#[derive(PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::app::maze::Topology;
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum MazeAlgorithms {
     Prims,
     Kruskals,
@@ -8,6 +12,15 @@ pub enum MazeAlgorithms {
     AldousBroder,
 }
 
+impl MazeAlgorithms {
+    pub const ALL: [MazeAlgorithms; 4] = [
+        MazeAlgorithms::Prims,
+        MazeAlgorithms::Kruskals,
+        MazeAlgorithms::Dfs,
+        MazeAlgorithms::AldousBroder,
+    ];
+}
+
 impl fmt::Display for MazeAlgorithms {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = match self {
@@ -21,12 +34,91 @@ impl fmt::Display for MazeAlgorithms {
 }
 
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum PathfindingAlgorithms {
     Astar,
     Dijkstra,
     Bfs,
     Dfs,
+    GreedyBestFirst,
+    WeightedAstar,
+    BidirectionalBfs,
+    BidirectionalAstar,
+    JumpPointSearch,
+    IterativeDeepeningDfs,
+    IdaStar,
+    LeftWallFollower,
+    RightWallFollower,
+    Pledge,
+    Tremaux,
+    DeadEndFilling,
+    DStarLite,
+}
+
+impl PathfindingAlgorithms {
+    /// Every pathfinder, in the order they are raced against each other.
+    pub const ALL: [PathfindingAlgorithms; 17] = [
+        PathfindingAlgorithms::Astar,
+        PathfindingAlgorithms::Dijkstra,
+        PathfindingAlgorithms::Bfs,
+        PathfindingAlgorithms::Dfs,
+        PathfindingAlgorithms::GreedyBestFirst,
+        PathfindingAlgorithms::WeightedAstar,
+        PathfindingAlgorithms::BidirectionalBfs,
+        PathfindingAlgorithms::BidirectionalAstar,
+        PathfindingAlgorithms::JumpPointSearch,
+        PathfindingAlgorithms::IterativeDeepeningDfs,
+        PathfindingAlgorithms::IdaStar,
+        PathfindingAlgorithms::LeftWallFollower,
+        PathfindingAlgorithms::RightWallFollower,
+        PathfindingAlgorithms::Pledge,
+        PathfindingAlgorithms::Tremaux,
+        PathfindingAlgorithms::DeadEndFilling,
+        PathfindingAlgorithms::DStarLite,
+    ];
+
+    /// Whether the search is guided by a heuristic towards the goal.
+    pub fn uses_heuristic(&self) -> bool {
+        matches!(
+            self,
+            PathfindingAlgorithms::Astar
+                | PathfindingAlgorithms::GreedyBestFirst
+                | PathfindingAlgorithms::WeightedAstar
+                | PathfindingAlgorithms::BidirectionalAstar
+                | PathfindingAlgorithms::JumpPointSearch
+                | PathfindingAlgorithms::IdaStar
+        )
+    }
+
+    /// Whether a second search grows from the goal to meet the one from the start.
+    pub fn is_bidirectional(&self) -> bool {
+        matches!(
+            self,
+            PathfindingAlgorithms::BidirectionalBfs | PathfindingAlgorithms::BidirectionalAstar
+        )
+    }
+
+    /// Whether the search keeps only its current path in memory and restarts
+    /// with a larger bound instead of remembering every cell it has seen.
+    pub fn is_iterative_deepening(&self) -> bool {
+        matches!(
+            self,
+            PathfindingAlgorithms::IterativeDeepeningDfs | PathfindingAlgorithms::IdaStar
+        )
+    }
+
+    /// Whether the solver is one of the human strategies that walk the maze
+    /// (or fill it in) rather than search it.
+    pub fn is_agent(&self) -> bool {
+        matches!(
+            self,
+            PathfindingAlgorithms::LeftWallFollower
+                | PathfindingAlgorithms::RightWallFollower
+                | PathfindingAlgorithms::Pledge
+                | PathfindingAlgorithms::Tremaux
+                | PathfindingAlgorithms::DeadEndFilling
+        )
+    }
 }
 
 impl fmt::Display for PathfindingAlgorithms {
@@ -36,6 +128,73 @@ impl fmt::Display for PathfindingAlgorithms {
             PathfindingAlgorithms::Bfs => "BFS Algorithm",
             PathfindingAlgorithms::Dfs => "DFS Algorithm",
             PathfindingAlgorithms::Dijkstra => "Dijkstra's Algorithm",
+            PathfindingAlgorithms::GreedyBestFirst => "Greedy Best-First",
+            PathfindingAlgorithms::WeightedAstar => "Weighted Astar",
+            PathfindingAlgorithms::BidirectionalBfs => "Bidirectional BFS",
+            PathfindingAlgorithms::BidirectionalAstar => "Bidirectional Astar",
+            PathfindingAlgorithms::JumpPointSearch => "Jump Point Search",
+            PathfindingAlgorithms::IterativeDeepeningDfs => "Iterative Deepening DFS",
+            PathfindingAlgorithms::IdaStar => "IDAstar Algorithm",
+            PathfindingAlgorithms::LeftWallFollower => "Left Wall Follower",
+            PathfindingAlgorithms::RightWallFollower => "Right Wall Follower",
+            PathfindingAlgorithms::Pledge => "Pledge Algorithm",
+            PathfindingAlgorithms::Tremaux => "Tremaux's Algorithm",
+            PathfindingAlgorithms::DeadEndFilling => "Dead-End Filling",
+            PathfindingAlgorithms::DStarLite => "DStar Lite (fog of war)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+
+/// Estimate of the remaining distance to the goal used by the informed searches.
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Heuristic {
+    Manhattan,
+    Euclidean,
+    Chebyshev,
+    Octile,
+    Zero,
+}
+
+impl Heuristic {
+    pub const ALL: [Heuristic; 5] = [
+        Heuristic::Manhattan,
+        Heuristic::Euclidean,
+        Heuristic::Chebyshev,
+        Heuristic::Octile,
+        Heuristic::Zero,
+    ];
+
+    /// Estimated steps from `from` to `to`. The grid metrics are made for square
+    /// cells; on hex cells, where a diagonal step moves only half a column, they
+    /// would overestimate, so they all count hex steps there instead.
+    /// Euclidean is measured between cell centres on either.
+    pub fn distance(&self, topology: Topology, from: (usize, usize), to: (usize, usize)) -> f64 {
+        if topology == Topology::Hex && !matches!(self, Heuristic::Euclidean | Heuristic::Zero) {
+            return topology.distance(from, to) as f64;
+        }
+        let ((from_x, from_y), (to_x, to_y)) = (topology.center(from), topology.center(to));
+        let (dx, dy) = ((from_x - to_x).abs(), (from_y - to_y).abs());
+
+        match self {
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Heuristic::Chebyshev => dx.max(dy),
+            Heuristic::Octile => dx.max(dy) + (std::f64::consts::SQRT_2 - 1.0) * dx.min(dy),
+            Heuristic::Zero => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for Heuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Heuristic::Manhattan => "Manhattan",
+            Heuristic::Euclidean => "Euclidean",
+            Heuristic::Chebyshev => "Chebyshev",
+            Heuristic::Octile => "Octile",
+            Heuristic::Zero => "Zero",
         };
         write!(f, "{}", name)
     }
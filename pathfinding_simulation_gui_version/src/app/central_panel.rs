@@ -51,17 +51,42 @@ impl Main {
 
                                             ui.vertical(|ui| {
                                                 ui.horizontal(|ui| {
-                                                    let generating = if window.generating {
+                                                    let generating = if window.is_playing() {
                                                         "Generating..."
+                                                    } else if !window.maze.is_generated() {
+                                                        "Paused"
+                                                    } else if window.solving {
+                                                        "Solving..."
+                                                    } else if let Some(fleet) = &window.maze.fleet {
+                                                        if fleet.solved {
+                                                            "All agents arrived!"
+                                                        } else {
+                                                            "No plan found!"
+                                                        }
+                                                    } else if let Some(solver) = &window.maze.solver {
+                                                        match solver.is_optimal() {
+                                                            None if solver.is_looping() => "Looping!",
+                                                            Some(true) => "Solved! (optimal)",
+                                                            Some(false) => "Solved! (suboptimal)",
+                                                            None => "No path!",
+                                                        }
                                                     } else {
                                                         "Done Generating!"
                                                     };
 
+                                                    let elapsed = if window.maze.solver.is_some() || window.maze.fleet.is_some() {
+                                                        window.solve_time
+                                                    } else {
+                                                        window.generation_time
+                                                    };
                                                     let timer = egui::Label::new(format!(
                                                         "{:.2}s",
-                                                        window.generation_time.as_secs_f64()
+                                                        elapsed.as_secs_f64()
                                                     ));
                                                     ui.heading(&window.title);
+                                                    if window.maze.is_generated() {
+                                                        validity_badge(window, ui);
+                                                    }
                                                     ui.add_space(ui.available_width() - 200.0);
                                                     ui.label(generating);
                                                     ui.add(timer);
@@ -71,6 +96,11 @@ impl Main {
                                                     }
                                                 });
 
+                                                playback_controls(window, ui);
+                                                solver_stats(window, ui);
+                                                fleet_stats(window, ui);
+                                                maze_stats(window, ui);
+
                                                 ui.separator();
                                                 draw_image(window, ui, ctx, &mut self.selected_window_id);
                                             });
@@ -131,3 +161,165 @@ fn draw_image(window: &mut WindowState, ui: &mut Ui, ctx: &Context, selected_win
         }
     }
 }
+
+fn playback_controls(window: &mut WindowState, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        let play_label = if window.is_playing() { "Pause" } else { "Play" };
+        if ui.button(play_label).clicked() {
+            if window.is_playing() {
+                window.pause();
+            } else {
+                window.play();
+            }
+        }
+
+        if ui.button("Step").clicked() {
+            window.step_once();
+        }
+
+        // Scrub through the recorded generation history
+        let mut cursor = window.maze.history_cursor;
+        let timeline = ui.add(
+            egui::Slider::new(&mut cursor, 0..=window.maze.history.len())
+                .show_value(false),
+        );
+        if timeline.changed() {
+            window.seek(cursor);
+        }
+
+        let internals = ui.checkbox(&mut window.show_internals, "Internals").on_hover_text(
+            "Draws what the generator is working with: the DFS stack and current cell, \
+             Prim's frontier, the Aldous-Broder walker, and Kruskal's sets with the edge it just looked at",
+        );
+        if internals.changed() {
+            window.needs_redraw = true;
+        }
+    });
+}
+
+/// Green when the maze is perfect, otherwise lists what is off about it.
+fn validity_badge(window: &mut WindowState, ui: &mut Ui) {
+    let validation = window.validation();
+    let (text, color) = if validation.is_perfect() {
+        ("✔ perfect".to_string(), egui::Color32::from_rgb(40, 160, 70))
+    } else if validation.is_consistent() && validation.is_connected() {
+        (format!("{} loops", validation.cycles), egui::Color32::from_rgb(60, 120, 200))
+    } else {
+        (format!("⚠ {}", validation), egui::Color32::from_rgb(220, 120, 30))
+    };
+    ui.label(egui::RichText::new(text).color(color));
+}
+
+fn solver_stats(window: &WindowState, ui: &mut Ui) {
+    if let Some(diameter) = &window.maze.diameter {
+        let bound = if diameter.exact { "" } else { "at least " };
+        ui.label(format!(
+            "diameter: {}{} from {:?} to {:?}",
+            bound, diameter.length, diameter.ends.0, diameter.ends.1
+        ));
+    }
+    if let Some(field) = &window.maze.distance_field {
+        ui.label(format!("farthest from {:?}: {} steps", field.source, field.max));
+    }
+
+    let Some(solver) = &window.maze.solver else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        match &solver.backward {
+            Some(backward) => ui.label(format!(
+                "expanded: {} from start + {} from goal",
+                solver.forward.expanded, backward.expanded
+            )),
+            None => ui.label(format!("expanded: {}", solver.forward.expanded)),
+        };
+        ui.label(format!("peak memory: {} cells", solver.peak_memory()));
+        if solver.explorer.is_some() || window.maze.dynamic_walls.is_some() {
+            ui.label(format!("replans: {}", solver.replans));
+        }
+        if let Some(deepening) = &solver.deepening {
+            ui.label(format!("iteration {} (bound {:.1})", deepening.iteration, deepening.bound));
+        }
+        if let Some(cost) = solver.path_cost() {
+            ui.label(format!("path cost: {}", cost));
+        }
+    });
+}
+
+fn fleet_stats(window: &WindowState, ui: &mut Ui) {
+    let Some(fleet) = &window.maze.fleet else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(format!("agents: {}", fleet.agents.len()));
+        if fleet.planned && !fleet.solved {
+            ui.label(format!("gave up after {} priority orders", fleet.attempts));
+        } else if fleet.attempts > 1 {
+            ui.label(format!("priority orders tried: {}", fleet.attempts));
+        }
+        if let (Some(makespan), Some(sum_of_costs)) = (fleet.makespan(), fleet.sum_of_costs()) {
+            ui.label(format!("timestep: {}/{}", fleet.time, makespan));
+            ui.label(format!("makespan: {}", makespan));
+            ui.label(format!("sum of costs: {}", sum_of_costs));
+        }
+    });
+}
+
+fn maze_stats(window: &mut WindowState, ui: &mut Ui) {
+    if !window.maze.is_generated() {
+        return;
+    }
+
+    let id = window.id;
+    egui::CollapsingHeader::new("Maze statistics")
+        .id_salt(id)
+        .show(ui, |ui| {
+            // Only measured while the panel is open, and then once per change to the maze
+            let (start, goal) = window.maze.suggested_endpoints();
+            let stats = window.stats();
+            let percent = |count: usize| 100.0 * count as f64 / stats.cells as f64;
+
+            egui::Grid::new(("maze_stats", id)).num_columns(4).show(ui, |ui| {
+                ui.label("dead ends:");
+                ui.label(format!("{} ({:.1}%)", stats.dead_ends, 100.0 * stats.dead_end_ratio));
+                ui.label("corridors:");
+                ui.label(format!("{} ({:.1}%)", stats.corridors, percent(stats.corridors)));
+                ui.end_row();
+
+                ui.label("junctions:");
+                ui.label(format!("{} ({:.1}%)", stats.junctions, percent(stats.junctions)));
+                ui.label("crossroads:");
+                ui.label(format!("{} ({:.1}%)", stats.crossroads, percent(stats.crossroads)));
+                ui.end_row();
+
+                ui.label("average corridor:");
+                ui.label(format!("{:.2} cells", stats.average_corridor));
+                ui.label("river factor:");
+                ui.label(format!("{:.2}", stats.river_factor));
+                ui.end_row();
+
+                ui.label(format!("solution {:?} to {:?}:", start, goal));
+                match stats.solution_length {
+                    Some(length) => ui.label(format!("{} steps", length)),
+                    None => ui.label("unreachable"),
+                };
+                ui.label("tortuosity:");
+                match stats.tortuosity {
+                    Some(tortuosity) => ui.label(format!("{:.2}", tortuosity)),
+                    None => ui.label("-"),
+                };
+                ui.end_row();
+
+                ui.label("passages:");
+                ui.label(format!(
+                    "{} horizontal, {} vertical",
+                    stats.horizontal_passages, stats.vertical_passages
+                ));
+                ui.label("direction bias:");
+                ui.label(format!("{:+.3}", stats.direction_bias));
+                ui.end_row();
+            });
+        });
+}
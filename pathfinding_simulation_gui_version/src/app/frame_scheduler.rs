@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// Number of frames the rolling FPS and frame time graph is computed over.
+const FRAME_HISTORY: usize = 120;
+
+/// Share of each frame that may be spent stepping mazes, the rest is left for the UI.
+const STEP_BUDGET_FRACTION: f64 = 0.5;
+
+/// Paces maze stepping against the desired frame rate. Measures how long a
+/// single step takes and lowers the steps per frame when the requested
+/// visualization speed would not fit in the frame's time budget.
+pub struct FrameScheduler {
+    frame_times: VecDeque<f32>, // Seconds between consecutive frames
+    last_frame: Option<Instant>,
+    secs_per_step: f64,         // Smoothed cost of a single step
+    pub steps_this_frame: usize,
+}
+
+impl Default for FrameScheduler {
+    fn default() -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            last_frame: None,
+            secs_per_step: 0.0,
+            steps_this_frame: 0,
+        }
+    }
+}
+
+impl FrameScheduler {
+    /// Records the start of a new frame and returns how many steps each maze
+    /// may take in it.
+    pub fn begin_frame(&mut self, requested_steps: usize, desired_fps: usize) -> usize {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            if self.frame_times.len() == FRAME_HISTORY {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back((now - last_frame).as_secs_f32());
+        }
+        self.last_frame = Some(now);
+
+        let budget = Self::frame_interval(desired_fps).as_secs_f64() * STEP_BUDGET_FRACTION;
+        let affordable = if self.secs_per_step > 0.0 {
+            (budget / self.secs_per_step) as usize
+        } else {
+            requested_steps
+        };
+
+        self.steps_this_frame = requested_steps.min(affordable).max(1);
+        self.steps_this_frame
+    }
+
+    /// Feeds back how long stepping took so the next frame can adapt.
+    pub fn end_stepping(&mut self, steps: usize, elapsed: Duration) {
+        if steps == 0 {
+            return;
+        }
+        let sample = elapsed.as_secs_f64() / steps as f64;
+        self.secs_per_step = if self.secs_per_step == 0.0 {
+            sample
+        } else {
+            self.secs_per_step * 0.9 + sample * 0.1
+        };
+    }
+
+    /// Time left until the next frame is due.
+    pub fn time_until_next_frame(&self, desired_fps: usize) -> Duration {
+        let elapsed = self.last_frame.map_or(Duration::ZERO, |start| start.elapsed());
+        Self::frame_interval(desired_fps).saturating_sub(elapsed)
+    }
+
+    pub fn frame_interval(desired_fps: usize) -> Duration {
+        Duration::from_secs_f64(1.0 / desired_fps.max(1) as f64)
+    }
+
+    /// Average frames per second over the recent frame history.
+    pub fn fps(&self) -> Option<f32> {
+        let total: f32 = self.frame_times.iter().sum();
+        (total > 0.0).then(|| self.frame_times.len() as f32 / total)
+    }
+
+    /// Recent frame times in seconds, oldest first.
+    pub fn frame_times(&self) -> &VecDeque<f32> {
+        &self.frame_times
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requested_steps_until_measured() {
+        let mut scheduler = FrameScheduler::default();
+        assert_eq!(scheduler.begin_frame(500, 60), 500);
+    }
+
+    #[test]
+    fn test_slow_steps_are_capped_by_budget() {
+        let mut scheduler = FrameScheduler::default();
+        scheduler.begin_frame(1000, 50);
+
+        // 1000 steps took 100ms, so a 50 fps frame (10ms budget) fits 100 steps
+        scheduler.end_stepping(1000, Duration::from_millis(100));
+        assert_eq!(scheduler.begin_frame(1000, 50), 100);
+
+        // Never drops below a single step, even on very slow machines
+        scheduler.end_stepping(1, Duration::from_secs(10));
+        assert_eq!(scheduler.begin_frame(1000, 50), 1);
+    }
+
+    #[test]
+    fn test_fps_from_frame_times() {
+        let mut scheduler = FrameScheduler::default();
+        assert_eq!(scheduler.fps(), None);
+
+        scheduler.frame_times.extend([0.02, 0.02, 0.02, 0.02]);
+        assert!((scheduler.fps().unwrap() - 50.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,201 @@
+use pathfinding_simulation_core::{DeadEndFilling, Passages, WalkStep, Walker, WalkerRule};
+use serde::{Deserialize, Serialize};
+
+use crate::app::PathfindingAlgorithms;
+
+use super::solver::{CellMark, MazeSolver};
+use super::{Maze, Topology};
+
+// Strategies a person could follow standing inside the maze. The walkers and
+// dead-end filling themselves are shared with the terminal version, this side
+// steps them one move at a time and colours in what they do.
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Agent {
+    Walker(Walker),
+    DeadEndFilling(DeadEndFilling),
+}
+
+impl MazeSolver {
+    /// Whether a wall follower (or Pledge walker) has been found walking in circles.
+    pub fn is_looping(&self) -> bool {
+        matches!(&self.agent, Some(Agent::Walker(walker)) if walker.looped)
+    }
+}
+
+impl Passages for Maze {
+    fn topology(&self) -> Topology {
+        self.grid.topology()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn passage(&self, cell: (usize, usize), wall: usize) -> Option<(usize, usize)> {
+        Maze::passage(self, cell, wall)
+    }
+}
+
+impl Maze {
+    pub(super) fn new_agent(&self, solver: &mut MazeSolver) {
+        if solver.algorithm == PathfindingAlgorithms::DeadEndFilling {
+            let filling = DeadEndFilling::new(self, solver.start, solver.goal);
+            for &cell in filling.dead_ends() {
+                solver.forward.marks[self.index(cell)] = CellMark::Open;
+            }
+            solver.agent = Some(Agent::DeadEndFilling(filling));
+            return;
+        }
+
+        let rule = match solver.algorithm {
+            PathfindingAlgorithms::LeftWallFollower => WalkerRule::LeftWallFollower,
+            PathfindingAlgorithms::RightWallFollower => WalkerRule::RightWallFollower,
+            PathfindingAlgorithms::Pledge => WalkerRule::Pledge,
+            _ => WalkerRule::Tremaux,
+        };
+        solver.forward.marks[self.index(solver.start)] = CellMark::Open;
+        solver.forward.peak_memory = 1;
+        solver.agent = Some(Agent::Walker(Walker::new(self, rule, solver.start, solver.goal)));
+    }
+
+    /// Moves the walker one cell, or fills one dead end. Returns false once the
+    /// goal is reached or the strategy cannot make any more progress.
+    pub(super) fn step_agent(&self, solver: &mut MazeSolver) -> bool {
+        match solver.agent.take() {
+            Some(Agent::Walker(mut walker)) => {
+                let still_running = self.step_walker(solver, &mut walker);
+                solver.agent = Some(Agent::Walker(walker));
+                still_running
+            }
+            Some(Agent::DeadEndFilling(mut filling)) => {
+                let still_running = self.fill_dead_end(solver, &mut filling);
+                solver.agent = Some(Agent::DeadEndFilling(filling));
+                still_running
+            }
+            None => false,
+        }
+    }
+
+    fn step_walker(&self, solver: &mut MazeSolver, walker: &mut Walker) -> bool {
+        match walker.step(self) {
+            WalkStep::Moved { cut } => {
+                // Cells of a loop the walker just closed drop off the route
+                for cell in cut {
+                    solver.forward.marks[self.index(cell)] = CellMark::Closed;
+                }
+                solver.forward.marks[self.index(walker.position)] = CellMark::Open;
+                solver.forward.expanded += 1;
+                solver.forward.peak_memory = 1 + walker.marked_passages(); // Trémaux remembers its marks
+                true
+            }
+            WalkStep::Turned => true,
+            WalkStep::Arrived => {
+                let route = walker.route().iter().map(|&cell| self.index(cell)).collect();
+                self.set_path(solver, route);
+                false
+            }
+            WalkStep::Looped | WalkStep::Stuck => false,
+        }
+    }
+
+    fn fill_dead_end(&self, solver: &mut MazeSolver, filling: &mut DeadEndFilling) -> bool {
+        let Some(fill) = filling.step(self) else {
+            // Everything left unfilled is on a route between start and goal
+            if let Some(path) = filling.path(self) {
+                let path = path.into_iter().map(|cell| self.index(cell)).collect();
+                self.set_path(solver, path);
+            }
+            return false;
+        };
+
+        solver.forward.marks[self.index(fill.cell)] = CellMark::Closed;
+        solver.forward.expanded += 1;
+        for cell in fill.dead_ends {
+            solver.forward.marks[self.index(cell)] = CellMark::Open;
+        }
+
+        let memory = filling.filled_count + filling.dead_ends().len();
+        solver.forward.peak_memory = solver.forward.peak_memory.max(memory);
+        true
+    }
+
+    fn index(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::app::maze::{generated, solve, solve_with, SolverOptions};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    const AGENTS: [PathfindingAlgorithms; 5] = [
+        PathfindingAlgorithms::LeftWallFollower,
+        PathfindingAlgorithms::RightWallFollower,
+        PathfindingAlgorithms::Pledge,
+        PathfindingAlgorithms::Tremaux,
+        PathfindingAlgorithms::DeadEndFilling,
+    ];
+
+    #[test]
+    fn test_agents_solve_perfect_mazes() {
+        let maze = generated(18, 14, MazeAlgorithms::Prims, 0.0);
+        let optimal = maze.shortest_path_cost((0, 0), (17, 13));
+
+        for algorithm in AGENTS {
+            let mut maze = maze.clone();
+            solve(&mut maze, algorithm);
+            let solver = maze.solver.as_ref().unwrap();
+
+            // A perfect maze has a single route, which every strategy ends up on
+            assert_eq!(solver.path_cost(), optimal, "{}", algorithm);
+            assert!(!solver.is_looping());
+            let path = solver.path.as_ref().unwrap();
+            for pair in path.windows(2) {
+                let (x, y) = pair[0];
+                assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wall_follower_detects_loop() {
+        // In an open room a wall follower never touches a wall and circles on the spot
+        let mut maze = Maze::new(4, 4);
+        maze.braid(1.0);
+
+        let mut follower = maze.clone();
+        let options = SolverOptions::default();
+        solve_with(&mut follower, PathfindingAlgorithms::RightWallFollower, options, (1, 1), (3, 3));
+        let solver = follower.solver.as_ref().unwrap();
+        assert!(solver.is_looping());
+        assert_eq!(solver.path_cost(), None);
+
+        // Trémaux remembers where it has been and gets there
+        let mut tremaux = maze.clone();
+        solve_with(&mut tremaux, PathfindingAlgorithms::Tremaux, options, (1, 1), (3, 3));
+        assert!(tremaux.solver.as_ref().unwrap().path_cost().is_some());
+    }
+
+    #[test]
+    fn test_agents_give_up_on_unreachable_goal() {
+        let mut maze = generated(6, 6, MazeAlgorithms::Prims, 0.0);
+        maze.grid.set_walls(5, 5, &[true; 4]);
+
+        for algorithm in [PathfindingAlgorithms::Tremaux, PathfindingAlgorithms::DeadEndFilling] {
+            let mut maze = maze.clone();
+            solve(&mut maze, algorithm);
+            let solver = maze.solver.as_ref().unwrap();
+            assert!(solver.finished);
+            assert_eq!(solver.path_cost(), None, "{}", algorithm);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::Maze;
+
+// Numbers for comparing generators. Cells are classified by how many passages
+// leave them: one is a dead end, two a corridor, three a junction and four
+// or more (hex cells have up to six) a crossroads.
+
+#[derive(Clone, Default)]
+pub struct MazeStats {
+    pub cells: usize,
+    pub dead_ends: usize,
+    pub corridors: usize,
+    pub junctions: usize,
+    pub crossroads: usize,
+    pub dead_end_ratio: f64,
+    pub average_corridor: f64, // Cells in an unbranching run of corridor cells, on average
+    pub river_factor: f64,     // Cells from a dead end back to the nearest junction, on average
+    pub solution_length: Option<usize>,
+    pub tortuosity: Option<f64>, // Solution length over the steps it would take with no walls
+    pub horizontal_passages: usize, // Passages between cells in the same row
+    pub vertical_passages: usize,   // Passages from one row to the next, straight down or diagonal on hex cells
+    pub direction_bias: f64, // -1 when every passage is vertical, 1 when every one is horizontal
+}
+
+impl Maze {
+    /// Measures the maze, with the solution taken between `start` and `goal`.
+    pub fn analyze(&self, start: (usize, usize), goal: (usize, usize)) -> MazeStats {
+        let degree = |(x, y): (usize, usize)| self.open_neighbors(x, y).len();
+        let cells: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .collect();
+
+        let mut stats = MazeStats {
+            cells: cells.len(),
+            ..MazeStats::default()
+        };
+        for &cell in &cells {
+            match degree(cell) {
+                1 => stats.dead_ends += 1,
+                2 => stats.corridors += 1,
+                3 => stats.junctions += 1,
+                4.. => stats.crossroads += 1,
+                _ => {}
+            }
+        }
+        for (x, y, wall) in self.grid.interior_walls() {
+            match self.passage((x, y), wall) {
+                Some((_, ny)) if ny == y => stats.horizontal_passages += 1,
+                Some(_) => stats.vertical_passages += 1,
+                None => {}
+            }
+        }
+        stats.dead_end_ratio = stats.dead_ends as f64 / stats.cells as f64;
+
+        // Runs of corridor cells joined to each other, each one counted once
+        let mut in_run = vec![false; cells.len()];
+        let mut runs = 0;
+        for &cell in &cells {
+            if degree(cell) != 2 || in_run[cell.1 * self.width + cell.0] {
+                continue;
+            }
+            runs += 1;
+            let mut stack = vec![cell];
+            in_run[cell.1 * self.width + cell.0] = true;
+            while let Some((x, y)) = stack.pop() {
+                for next in self.open_neighbors(x, y) {
+                    if degree(next) == 2 && !in_run[next.1 * self.width + next.0] {
+                        in_run[next.1 * self.width + next.0] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        if runs > 0 {
+            stats.average_corridor = stats.corridors as f64 / runs as f64;
+        }
+
+        // Walk from every dead end until the passage branches
+        let mut branch_cells = 0;
+        for &cell in cells.iter().filter(|&&cell| degree(cell) == 1) {
+            let (mut previous, mut current) = (cell, cell);
+            loop {
+                branch_cells += 1;
+                let onward: Vec<_> = self
+                    .open_neighbors(current.0, current.1)
+                    .into_iter()
+                    .filter(|&next| next != previous)
+                    .collect();
+                match onward[..] {
+                    [next] if degree(next) <= 2 && next != cell => (previous, current) = (current, next),
+                    _ => break,
+                }
+            }
+        }
+        if stats.dead_ends > 0 {
+            stats.river_factor = branch_cells as f64 / stats.dead_ends as f64;
+        }
+
+        let field = self.distance_field(start);
+        stats.solution_length = field.distance[goal.1 * self.width + goal.0];
+        let straight = self.grid.topology().distance(start, goal);
+        if straight > 0 {
+            stats.tortuosity = stats.solution_length.map(|length| length as f64 / straight as f64);
+        }
+
+        let passages = stats.horizontal_passages + stats.vertical_passages;
+        if passages > 0 {
+            stats.direction_bias =
+                (stats.horizontal_passages as f64 - stats.vertical_passages as f64) / passages as f64;
+        }
+        stats
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::app::maze::{generated, MazeEvent};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    #[test]
+    fn test_cell_counts_add_up() {
+        let maze = generated(16, 12, MazeAlgorithms::Dfs, 0.0);
+
+        let stats = maze.analyze((0, 0), (15, 11));
+        assert_eq!(stats.dead_ends + stats.corridors + stats.junctions + stats.crossroads, stats.cells);
+        // A perfect maze is a spanning tree
+        assert_eq!(stats.horizontal_passages + stats.vertical_passages, stats.cells - 1);
+        assert!(stats.tortuosity.unwrap() >= 1.0);
+        assert!(stats.direction_bias.abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_single_corridor() {
+        let mut maze = Maze::new(5, 1);
+        for x in 0..4 {
+            maze.record(MazeEvent::RemoveWall { x, y: 0, wall: 1 });
+        }
+
+        let stats = maze.analyze((0, 0), (4, 0));
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.corridors, 3);
+        assert_eq!(stats.average_corridor, 3.0);
+        assert_eq!(stats.solution_length, Some(4));
+        assert_eq!(stats.tortuosity, Some(1.0));
+        assert_eq!(stats.direction_bias, 1.0);
+    }
+
+    #[test]
+    fn test_river_factor_measures_dead_end_branches() {
+        // A comb: a corridor along the top with a spur of length 2 hanging off every other cell
+        let mut maze = Maze::new(5, 3);
+        for x in 0..4 {
+            maze.record(MazeEvent::RemoveWall { x, y: 0, wall: 1 });
+        }
+        for x in [0, 2, 4] {
+            maze.record(MazeEvent::RemoveWall { x, y: 0, wall: 2 });
+            maze.record(MazeEvent::RemoveWall { x, y: 1, wall: 2 });
+        }
+
+        let stats = maze.analyze((0, 2), (4, 2));
+        assert_eq!(stats.dead_ends, 3);
+        assert_eq!(stats.junctions, 1);
+        // The middle spur stops at the junction, the outer ones run on around the corner
+        assert_eq!(stats.river_factor, (2.0 + 4.0 + 4.0) / 3.0);
+        assert_eq!(stats.solution_length, Some(8));
+    }
+}
@@ -0,0 +1,333 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use super::grid::MAX_WALLS;
+use super::solver::{CellMark, MazeSolver};
+use super::Maze;
+
+// D* Lite for an agent that starts out knowing nothing about the maze. It
+// assumes every wall it has not seen is open, plans from the goal back to
+// itself, and walks the plan. Walls that come into sensor range change edge
+// costs, and only the part of the plan that depends on them is repaired.
+
+/// Stands in for an infinite distance, small enough that adding to it cannot overflow.
+const UNREACHABLE: usize = usize::MAX / 4;
+
+type Key = (usize, usize);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Explorer {
+    pub position: (usize, usize),
+    last_replan: (usize, usize), // Where the agent stood at the last replan, for the key modifier
+    key_modifier: usize,         // km: how far the heuristic's anchor has moved since planning began
+    g: Vec<usize>,
+    rhs: Vec<usize>,
+    queue: BinaryHeap<Reverse<(Key, usize)>>,
+    queued: Vec<Option<Key>>, // Current key of each queued cell, older heap entries are stale
+    walls: Vec<[bool; MAX_WALLS]>, // Walls the agent knows about
+    pub known: Vec<bool>,     // Cells that have been within sensor range
+    pub planned: Vec<bool>,   // Cells on the route the agent currently intends to take
+    trajectory: Vec<usize>,   // Every cell the agent has stood on, in order
+    planning: bool,
+}
+
+impl Maze {
+    pub(super) fn new_explorer(&self, solver: &mut MazeSolver) {
+        let cells = self.width * self.height;
+        let start = solver.start.1 * self.width + solver.start.0;
+
+        let mut explorer = Explorer {
+            position: solver.start,
+            last_replan: solver.start,
+            key_modifier: 0,
+            g: vec![UNREACHABLE; cells],
+            rhs: vec![UNREACHABLE; cells],
+            queue: BinaryHeap::new(),
+            queued: vec![None; cells],
+            walls: vec![[false; MAX_WALLS]; cells],
+            known: vec![false; cells],
+            planned: vec![false; cells],
+            trajectory: vec![start],
+            planning: true,
+        };
+        self.sense(solver, &mut explorer);
+
+        // Plan backwards from the goal, so the agent moving only shifts the heuristic
+        let goal = solver.goal.1 * self.width + solver.goal.0;
+        explorer.rhs[goal] = 0;
+        let key = self.key(&explorer, goal);
+        self.enqueue(&mut explorer, goal, key);
+
+        solver.forward.marks[start] = CellMark::Path;
+        solver.explorer = Some(explorer);
+    }
+
+    /// Either repairs the plan by one expansion or, once the plan is
+    /// consistent, moves the agent one cell along it.
+    pub(super) fn step_explorer(&self, solver: &mut MazeSolver) -> bool {
+        let Some(mut explorer) = solver.explorer.take() else {
+            return false;
+        };
+        let still_running = if explorer.planning {
+            self.plan_step(solver, &mut explorer);
+            true
+        } else {
+            self.move_agent(solver, &mut explorer)
+        };
+        solver.explorer = Some(explorer);
+        still_running
+    }
+
+    fn plan_step(&self, solver: &mut MazeSolver, explorer: &mut Explorer) {
+        let start = self.cell(explorer.position);
+        let start_key = self.key(explorer, start);
+
+        let Some(top_key) = self.top_key(explorer) else {
+            explorer.planning = false;
+            self.trace_plan(explorer);
+            return;
+        };
+        if top_key >= start_key && explorer.rhs[start] == explorer.g[start] {
+            explorer.planning = false;
+            self.trace_plan(explorer);
+            return;
+        }
+
+        let Some(Reverse((old_key, cell))) = explorer.queue.pop() else {
+            return;
+        };
+        explorer.queued[cell] = None;
+        solver.forward.expanded += 1;
+        if solver.forward.marks[cell] == CellMark::Unseen {
+            solver.forward.marks[cell] = CellMark::Closed;
+        }
+
+        let new_key = self.key(explorer, cell);
+        if old_key < new_key {
+            self.enqueue(explorer, cell, new_key); // The agent moved since this was queued
+        } else if explorer.g[cell] > explorer.rhs[cell] {
+            explorer.g[cell] = explorer.rhs[cell];
+            for neighbor in self.believed_neighbors(explorer, cell) {
+                self.update_vertex(solver, explorer, neighbor);
+            }
+        } else {
+            explorer.g[cell] = UNREACHABLE;
+            self.update_vertex(solver, explorer, cell);
+            for neighbor in self.believed_neighbors(explorer, cell) {
+                self.update_vertex(solver, explorer, neighbor);
+            }
+        }
+
+        let memory = explorer.queue.len();
+        solver.forward.peak_memory = solver.forward.peak_memory.max(memory);
+    }
+
+    fn move_agent(&self, solver: &mut MazeSolver, explorer: &mut Explorer) -> bool {
+        let current = self.cell(explorer.position);
+        if explorer.position == solver.goal {
+            self.set_path(solver, explorer.trajectory.clone());
+            return false;
+        }
+        if explorer.g[current] >= UNREACHABLE {
+            return false; // Even with every unseen wall assumed open there is no way through
+        }
+
+        let Some(next) = self
+            .believed_neighbors(explorer, current)
+            .into_iter()
+            .min_by_key(|&neighbor| explorer.g[neighbor])
+        else {
+            return false;
+        };
+
+        explorer.position = (next % self.width, next / self.width);
+        explorer.planned[current] = false;
+        explorer.trajectory.push(next);
+        solver.forward.marks[next] = CellMark::Path;
+
+        let changed = self.sense(solver, explorer);
+        if !changed.is_empty() {
+            explorer.key_modifier += self.distance(explorer.last_replan, explorer.position);
+            explorer.last_replan = explorer.position;
+            for (cell, neighbor) in changed {
+                self.update_vertex(solver, explorer, cell);
+                self.update_vertex(solver, explorer, neighbor);
+            }
+            explorer.planning = true;
+            solver.replans += 1;
+        }
+        true
+    }
+
+    /// Looks at the walls of every cell within the sensor radius. Returns the
+    /// pairs of cells whose connecting wall turned out different from what
+    /// the agent believed, including doors that opened or closed since it last looked.
+    fn sense(&self, solver: &MazeSolver, explorer: &mut Explorer) -> Vec<(usize, usize)> {
+        let radius = solver.options.sensor_radius;
+        let (px, py) = explorer.position;
+        let mut changed = Vec::new();
+
+        for y in py.saturating_sub(radius)..(py + radius + 1).min(self.height) {
+            for x in px.saturating_sub(radius)..(px + radius + 1).min(self.width) {
+                let (dx, dy) = (x.abs_diff(px), y.abs_diff(py));
+                let cell = self.cell((x, y));
+                if dx * dx + dy * dy > radius * radius {
+                    continue;
+                }
+                explorer.known[cell] = true;
+
+                for wall in 0..self.grid.wall_count() {
+                    let Some(neighbor) = self.neighbor_across((x, y), wall) else {
+                        continue; // The outer boundary is known from the start
+                    };
+                    let actual = self.grid.wall(x, y, wall);
+                    if actual != explorer.walls[cell][wall] {
+                        explorer.walls[cell][wall] = actual;
+                        explorer.walls[neighbor][self.grid.topology().opposite(wall)] = actual;
+                        changed.push((cell, neighbor));
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    fn update_vertex(&self, solver: &MazeSolver, explorer: &mut Explorer, cell: usize) {
+        if cell != self.cell(solver.goal) {
+            explorer.rhs[cell] = self
+                .believed_neighbors(explorer, cell)
+                .into_iter()
+                .map(|neighbor| explorer.g[neighbor] + 1)
+                .min()
+                .unwrap_or(UNREACHABLE)
+                .min(UNREACHABLE);
+        }
+
+        explorer.queued[cell] = None;
+        if explorer.g[cell] != explorer.rhs[cell] {
+            let key = self.key(explorer, cell);
+            self.enqueue(explorer, cell, key);
+        }
+    }
+
+    fn key(&self, explorer: &Explorer, cell: usize) -> Key {
+        let best = explorer.g[cell].min(explorer.rhs[cell]);
+        let heuristic = self.distance(explorer.position, (cell % self.width, cell / self.width));
+        (best + heuristic + explorer.key_modifier, best)
+    }
+
+    fn enqueue(&self, explorer: &mut Explorer, cell: usize, key: Key) {
+        explorer.queued[cell] = Some(key);
+        explorer.queue.push(Reverse((key, cell)));
+    }
+
+    /// Smallest key still queued, dropping stale heap entries on the way.
+    fn top_key(&self, explorer: &mut Explorer) -> Option<Key> {
+        while let Some(&Reverse((key, cell))) = explorer.queue.peek() {
+            if explorer.queued[cell] == Some(key) {
+                return Some(key);
+            }
+            explorer.queue.pop();
+        }
+        None
+    }
+
+    /// Marks the cells the agent would walk through if the maze matched its beliefs.
+    fn trace_plan(&self, explorer: &mut Explorer) {
+        explorer.planned.fill(false);
+        let mut current = self.cell(explorer.position);
+        while explorer.g[current] > 0 && explorer.g[current] < UNREACHABLE {
+            let Some(next) = self
+                .believed_neighbors(explorer, current)
+                .into_iter()
+                .filter(|&neighbor| explorer.g[neighbor] < explorer.g[current])
+                .min_by_key(|&neighbor| explorer.g[neighbor])
+            else {
+                break;
+            };
+            explorer.planned[next] = true;
+            current = next;
+        }
+    }
+
+    /// Neighbours the agent believes it can walk to: any wall it has not seen is assumed open.
+    fn believed_neighbors(&self, explorer: &Explorer, cell: usize) -> Vec<usize> {
+        let position = (cell % self.width, cell / self.width);
+        (0..self.grid.wall_count())
+            .filter(|&wall| !explorer.walls[cell][wall])
+            .filter_map(|wall| self.neighbor_across(position, wall))
+            .collect()
+    }
+
+    fn neighbor_across(&self, (x, y): (usize, usize), wall: usize) -> Option<usize> {
+        self.grid.neighbor(x, y, wall).map(|neighbor| self.cell(neighbor))
+    }
+
+    fn distance(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        self.grid.topology().distance(from, to)
+    }
+
+    fn cell(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::app::algorithms::PathfindingAlgorithms;
+    use crate::app::maze::{generated, SolverOptions};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    fn explore(maze: &mut Maze, sensor_radius: usize) -> MazeSolver {
+        let options = SolverOptions { sensor_radius, ..SolverOptions::default() };
+        let goal = (maze.width - 1, maze.height - 1);
+        maze.init_solver(PathfindingAlgorithms::DStarLite, options, (0, 0), goal);
+        let mut solve_time = Duration::ZERO;
+        while maze.solve_step(1000, &mut solve_time) {}
+        maze.solver.clone().unwrap()
+    }
+
+    #[test]
+    fn test_explorer_reaches_goal_through_fog() {
+        for _ in 0..10 {
+            let mut maze = generated(16, 12, MazeAlgorithms::Dfs, 0.2);
+            let solver = explore(&mut maze, 1);
+            assert!(solver.replans > 0);
+
+            // The trajectory only ever steps through walls that really are open
+            let trajectory = solver.path.as_ref().unwrap();
+            assert_eq!(trajectory.first(), Some(&(0, 0)));
+            assert_eq!(trajectory.last(), Some(&(15, 11)));
+            for pair in trajectory.windows(2) {
+                let (x, y) = pair[0];
+                assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_explorer_with_full_view_walks_shortest_path() {
+        let mut maze = generated(14, 14, MazeAlgorithms::Dfs, 0.2);
+        let optimal = maze.shortest_path_cost((0, 0), (13, 13));
+        let solver = explore(&mut maze, 30);
+        assert_eq!(solver.path_cost(), optimal);
+        assert_eq!(solver.replans, 0);
+    }
+
+    #[test]
+    fn test_explorer_gives_up_when_walled_in() {
+        let mut maze = generated(8, 8, MazeAlgorithms::Dfs, 0.2);
+        maze.grid.set_walls(7, 7, &[true; 4]);
+
+        let solver = explore(&mut maze, 2);
+        assert!(solver.finished);
+        assert_eq!(solver.path_cost(), None);
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::Maze;
+
+// Every passage costs the same, so a breadth-first flood is already Dijkstra.
+// The flood gives each cell its distance to the source, and pointing each cell
+// at a neighbour one step closer gives a flow field any number of agents can
+// follow home without searching themselves.
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DistanceField {
+    pub source: (usize, usize),
+    pub distance: Vec<Option<usize>>, // Steps to the source, None where it cannot be reached
+    pub flow: Vec<Option<usize>>,     // Wall to leave through to get one step closer
+    pub max: usize,                   // Distance of the farthest reachable cell
+    pub farthest: (usize, usize),
+}
+
+/// The longest shortest path in the maze, between `ends.0` and `ends.1`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Diameter {
+    pub ends: ((usize, usize), (usize, usize)),
+    pub length: usize,
+    pub exact: bool, // The double sweep is only guaranteed to find it when the maze has no loops
+}
+
+impl DistanceField {
+    /// The cell a flow-following agent moves to from (x, y).
+    pub fn next_step(&self, maze: &Maze, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        let wall = self.flow[y * maze.width + x]?;
+        maze.passage((x, y), wall)
+    }
+}
+
+impl Maze {
+    /// Floods the maze from `source`, recording distances and the flow toward it.
+    pub fn distance_field(&self, source: (usize, usize)) -> DistanceField {
+        let mut field = DistanceField {
+            source,
+            distance: vec![None; self.width * self.height],
+            flow: vec![None; self.width * self.height],
+            max: 0,
+            farthest: source,
+        };
+        field.distance[source.1 * self.width + source.0] = Some(0);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some((x, y)) = queue.pop_front() {
+            let distance = field.distance[y * self.width + x].unwrap_or(0);
+            if distance > field.max {
+                field.max = distance;
+                field.farthest = (x, y);
+            }
+
+            for wall in 0..self.grid.wall_count() {
+                let Some((nx, ny)) = self.passage((x, y), wall) else {
+                    continue;
+                };
+                let index = ny * self.width + nx;
+                if field.distance[index].is_none() {
+                    field.distance[index] = Some(distance + 1);
+                    field.flow[index] = Some(self.grid.topology().opposite(wall)); // Back the way the flood came
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        field
+    }
+
+    /// Finds the diameter with two floods: the cell farthest from a corner is one
+    /// end, the cell farthest from that is the other. Exact on perfect mazes,
+    /// where the passages form a tree, and a lower bound once loops are added.
+    pub fn diameter(&self) -> Diameter {
+        let first = self.distance_field((0, 0));
+        let second = self.distance_field(first.farthest);
+
+        let cells = self.width * self.height;
+        let passages = self
+            .grid
+            .interior_walls()
+            .filter(|&(x, y, wall)| !self.grid.wall(x, y, wall))
+            .count();
+
+        Diameter {
+            ends: (first.farthest, second.farthest),
+            length: second.max,
+            exact: passages + 1 == cells && first.distance.iter().all(Option::is_some),
+        }
+    }
+
+    /// Start and goal for solving: the diameter's ends once it has been found,
+    /// opposite corners otherwise.
+    pub fn suggested_endpoints(&self) -> ((usize, usize), (usize, usize)) {
+        match &self.diameter {
+            Some(diameter) => diameter.ends,
+            None => ((0, 0), (self.width - 1, self.height - 1)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::maze::generated;
+    use crate::app::MazeAlgorithms;
+
+    #[test]
+    fn test_flow_field_leads_every_cell_home() {
+        let maze = generated(20, 15, MazeAlgorithms::Prims, 0.2);
+        let field = maze.distance_field((7, 4));
+
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                let mut cell = (x, y);
+                let mut steps = 0;
+                while let Some(next) = field.next_step(&maze, cell) {
+                    cell = next;
+                    steps += 1;
+                }
+                assert_eq!(cell, (7, 4));
+                assert_eq!(Some(steps), field.distance[y * maze.width + x]);
+            }
+        }
+        assert_eq!(Some(field.max), field.distance.iter().flatten().max().copied());
+    }
+
+    #[test]
+    fn test_diameter_matches_all_pairs() {
+        let maze = generated(12, 9, MazeAlgorithms::Prims, 0.0);
+        let diameter = maze.diameter();
+        assert!(diameter.exact);
+
+        let longest = (0..maze.height)
+            .flat_map(|y| (0..maze.width).map(move |x| (x, y)))
+            .map(|cell| maze.distance_field(cell).max)
+            .max();
+        assert_eq!(Some(diameter.length), longest);
+
+        let (from, to) = diameter.ends;
+        assert_eq!(maze.distance_field(from).distance[to.1 * maze.width + to.0], Some(diameter.length));
+    }
+
+    #[test]
+    fn test_unreachable_cells_have_no_distance() {
+        let maze = Maze::new(3, 3);
+        let field = maze.distance_field((1, 1));
+        assert_eq!(field.distance.iter().flatten().count(), 1);
+        assert_eq!(field.next_step(&maze, (0, 0)), None);
+        assert!(!maze.diameter().exact);
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::solver::{CellMark, MazeSolver};
+use super::Maze;
+
+/// How the doors decide when to open or close.
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DoorMode {
+    #[default]
+    Scheduled, // Every door toggles once per period, each with its own phase
+    Random,    // Doors toggle after a random wait averaging one period
+}
+
+impl fmt::Display for DoorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DoorMode::Scheduled => "Scheduled",
+            DoorMode::Random => "Random",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A wall that was opened or closed while solving. Always stored on the cell
+/// that comes first row by row, so on square cells `wall` is 1 (right) or 2 (bottom).
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct WallChange {
+    pub x: usize,
+    pub y: usize,
+    pub wall: usize,
+    pub open: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DynamicWalls {
+    pub mode: DoorMode,
+    period: usize,
+    pub clock: usize,                        // Solver steps taken since the doors were set up
+    doors: Vec<(usize, usize, usize)>,       // (x, y, wall) of every wall that can toggle
+    queue: VecDeque<(usize, usize)>,         // (clock, door) of upcoming toggles, soonest first
+    applied: Vec<WallChange>,                // Changes not yet picked up by the GUI
+}
+
+/// A pathfinder's plan being walked one cell per step, so that doors closing
+/// in front of it force a replan from wherever it stands.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Execution {
+    pub position: (usize, usize),
+    pub walked: Vec<(usize, usize)>,
+    route: VecDeque<(usize, usize)>, // Rest of the current plan
+    searching: bool,                 // Whether a replan is still being searched for
+    #[serde(default)]
+    waiting: Option<usize>, // Maze revision when a replan found no way through, until a door toggles
+}
+
+impl Maze {
+    /// Picks `door_count` random interior walls that will open and close while
+    /// a pathfinder runs, replacing any doors set up before.
+    pub fn enable_dynamic_walls(&mut self, mode: DoorMode, door_count: usize, period: usize) {
+        let period = period.max(1);
+
+        let candidates: Vec<(usize, usize, usize)> = self.grid.interior_walls().collect();
+        let doors: Vec<_> = candidates.choose_multiple(&mut self.rng, door_count).copied().collect();
+
+        let mut dynamic = DynamicWalls {
+            mode,
+            period,
+            clock: 0,
+            doors,
+            queue: VecDeque::new(),
+            applied: Vec::new(),
+        };
+        for door in 0..dynamic.doors.len() {
+            let first = self.rng.gen_range(1..=period); // Spread the doors out over the first period
+            dynamic.schedule(first, door);
+        }
+        self.dynamic_walls = Some(dynamic);
+    }
+
+    pub fn disable_dynamic_walls(&mut self) {
+        self.dynamic_walls = None;
+    }
+
+    /// Changes applied since the last call, for the GUI to flash.
+    pub fn take_wall_changes(&mut self) -> Vec<WallChange> {
+        self.dynamic_walls
+            .as_mut()
+            .map_or_else(Vec::new, |dynamic| std::mem::take(&mut dynamic.applied))
+    }
+
+    /// Advances the door clock by one step and toggles every door that is due.
+    pub(super) fn advance_dynamic_walls(&mut self) {
+        let Some(mut dynamic) = self.dynamic_walls.take() else {
+            return;
+        };
+        dynamic.clock += 1;
+
+        while let Some(&(at, door)) = dynamic.queue.front() {
+            if at > dynamic.clock {
+                break;
+            }
+            dynamic.queue.pop_front();
+
+            let (x, y, wall) = dynamic.doors[door];
+            let open = self.grid.wall(x, y, wall);
+            self.grid.set_wall(x, y, wall, !open);
+            self.revision += 1;
+            dynamic.applied.push(WallChange { x, y, wall, open });
+
+            let wait = match dynamic.mode {
+                DoorMode::Scheduled => dynamic.period,
+                DoorMode::Random => self.rng.gen_range(1..=2 * dynamic.period),
+            };
+            dynamic.schedule(at + wait, door);
+        }
+        self.dynamic_walls = Some(dynamic);
+    }
+
+    /// Whether a found path should be walked out step by step, rather than
+    /// finishing the moment it is found. Walkers and the fog of war explorer
+    /// already move through the maze themselves.
+    pub(super) fn executes_plan(&self, solver: &MazeSolver) -> bool {
+        self.dynamic_walls.is_some() && solver.agent.is_none() && solver.explorer.is_none()
+    }
+
+    /// Starts walking the path the search just found, from wherever the
+    /// pathfinder currently stands.
+    pub(super) fn follow_plan(&self, solver: &mut MazeSolver) {
+        let plan = solver.path.take().unwrap_or_default();
+        let execution = solver.execution.get_or_insert_with(|| Execution {
+            position: solver.start,
+            walked: vec![solver.start],
+            route: VecDeque::new(),
+            searching: false,
+            waiting: None,
+        });
+        execution.route = plan.into_iter().skip(1).collect();
+        execution.searching = false;
+    }
+
+    /// Keeps the pathfinder where it stands after a replan found no way
+    /// through, since a closed door may be all that is in the way.
+    pub(super) fn wait_for_door(&self, solver: &mut MazeSolver) {
+        if let Some(execution) = solver.execution.as_mut() {
+            execution.searching = false;
+            execution.waiting = Some(self.revision);
+        }
+    }
+
+    /// Whether the pathfinder is walking a plan, as opposed to searching for one.
+    pub(super) fn is_executing(&self, solver: &MazeSolver) -> bool {
+        solver.execution.as_ref().is_some_and(|execution| !execution.searching)
+    }
+
+    /// Moves one cell along the plan, or replans if a door has closed across it.
+    /// Returns false once the goal is reached.
+    pub(super) fn execute_step(&self, solver: &mut MazeSolver) -> bool {
+        let Some(mut execution) = solver.execution.take() else {
+            return false;
+        };
+
+        if execution.position == solver.goal {
+            for &(x, y) in &execution.walked {
+                solver.forward.marks[y * self.width + x] = CellMark::Path;
+            }
+            solver.path = Some(execution.walked.clone());
+            solver.execution = Some(execution);
+            return false;
+        }
+
+        // Stay put until some door has toggled, then search again
+        if let Some(revision) = execution.waiting {
+            if revision == self.revision {
+                solver.execution = Some(execution);
+                return true;
+            }
+            execution.waiting = None;
+        }
+
+        let (x, y) = execution.position;
+        match execution.route.front() {
+            Some(&next) if self.open_neighbors(x, y).contains(&next) => {
+                execution.route.pop_front();
+                execution.position = next;
+                execution.walked.push(next);
+                solver.execution = Some(execution);
+            }
+            _ => {
+                // The way ahead has closed: search again from here, keeping the totals
+                let mut replan = self.new_solver(solver.algorithm, solver.options, execution.position, solver.goal);
+                replan.start = solver.start;
+                replan.forward.expanded = solver.expanded();
+                replan.forward.peak_memory = replan.forward.peak_memory.max(solver.peak_memory());
+                replan.replans = solver.replans + 1;
+
+                execution.route.clear();
+                execution.searching = true;
+                replan.execution = Some(execution);
+                *solver = replan;
+            }
+        }
+        true
+    }
+}
+
+impl DynamicWalls {
+    fn schedule(&mut self, at: usize, door: usize) {
+        let index = self.queue.partition_point(|&(other, _)| other <= at);
+        self.queue.insert(index, (at, door));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::app::algorithms::PathfindingAlgorithms;
+    use crate::app::maze::{generated, SolverOptions};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    #[test]
+    fn test_scheduled_doors_toggle_every_period() {
+        let mut maze = generated(10, 10, MazeAlgorithms::Prims, 0.3);
+        maze.enable_dynamic_walls(DoorMode::Scheduled, 5, 10);
+
+        let mut changes = Vec::new();
+        for _ in 0..30 {
+            maze.advance_dynamic_walls();
+            changes.extend(maze.take_wall_changes());
+        }
+        // Each door toggles in the first period and then every 10 steps after
+        assert_eq!(changes.len(), 15);
+        for change in &changes {
+            let (x, y, wall) = (change.x, change.y, change.wall);
+            assert!(wall == 1 || wall == 2);
+            // Doors are interior walls, never part of the boundary
+            assert!(maze.grid.neighbor(x, y, wall).is_some());
+        }
+    }
+
+    #[test]
+    fn test_walked_path_only_uses_open_walls() {
+        for algorithm in [PathfindingAlgorithms::Astar, PathfindingAlgorithms::Bfs] {
+            let mut maze = generated(15, 15, MazeAlgorithms::Prims, 0.3);
+            maze.enable_dynamic_walls(DoorMode::Random, 40, 5);
+            maze.init_solver(algorithm, SolverOptions::default(), (0, 0), (14, 14));
+
+            let mut solve_time = Duration::ZERO;
+            let mut walked = 1;
+            for _ in 0..100_000 {
+                if !maze.solve_step(1, &mut solve_time) {
+                    break;
+                }
+                // Doors only toggle before the move within a step, so the current walls apply
+                if let Some(execution) = &maze.solver.as_ref().unwrap().execution {
+                    if execution.walked.len() > walked {
+                        let (x, y) = execution.walked[walked - 1];
+                        assert!(maze.open_neighbors(x, y).contains(&execution.position));
+                        walked = execution.walked.len();
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_closed_door_forces_replan() {
+        // A corridor along the top row, with a detour through the second row
+        let mut maze = Maze::new(4, 2);
+        maze.braid(1.0);
+        maze.dynamic_walls = Some(DynamicWalls {
+            mode: DoorMode::Scheduled,
+            period: 1000,
+            clock: 0,
+            doors: vec![(1, 0, 1)],
+            queue: VecDeque::new(),
+            applied: Vec::new(),
+        });
+        maze.init_solver(PathfindingAlgorithms::Astar, SolverOptions::default(), (0, 0), (3, 0));
+
+        // Let the search finish and the walk begin, then close the door ahead
+        let mut solve_time = Duration::ZERO;
+        while !maze.is_executing(maze.solver.as_ref().unwrap()) {
+            maze.solve_step(1, &mut solve_time);
+        }
+        let dynamic = maze.dynamic_walls.as_mut().unwrap();
+        dynamic.schedule(dynamic.clock + 1, 0);
+        while maze.solve_step(1, &mut solve_time) {}
+
+        let solver = maze.solver.as_ref().unwrap();
+        assert_eq!(solver.replans, 1);
+        let path = solver.path.as_ref().unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(3, 0)));
+        assert!(path.contains(&(1, 1)) || path.contains(&(2, 1)));
+    }
+
+    #[test]
+    fn test_waits_for_door_on_only_route() {
+        // A single corridor, with one door that closes in front of the pathfinder
+        let mut maze = Maze::new(4, 1);
+        maze.braid(1.0);
+        maze.dynamic_walls = Some(DynamicWalls {
+            mode: DoorMode::Scheduled,
+            period: 5,
+            clock: 0,
+            doors: vec![(1, 0, 1)],
+            queue: VecDeque::new(),
+            applied: Vec::new(),
+        });
+        maze.init_solver(PathfindingAlgorithms::Astar, SolverOptions::default(), (0, 0), (3, 0));
+
+        let mut solve_time = Duration::ZERO;
+        while !maze.is_executing(maze.solver.as_ref().unwrap()) {
+            maze.solve_step(1, &mut solve_time);
+        }
+        let dynamic = maze.dynamic_walls.as_mut().unwrap();
+        dynamic.schedule(dynamic.clock + 1, 0);
+
+        // The replan behind the closed door finds nothing, yet the walk goes on once it reopens
+        for _ in 0..1000 {
+            if !maze.solve_step(1, &mut solve_time) {
+                break;
+            }
+        }
+
+        let solver = maze.solver.as_ref().unwrap();
+        assert!(solver.finished);
+        assert!(solver.replans >= 2);
+        assert_eq!(solver.path.as_ref().unwrap(), &vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+    }
+}
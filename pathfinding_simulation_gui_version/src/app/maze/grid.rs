@@ -0,0 +1,202 @@
+pub use pathfinding_simulation_core::MAX_WALLS;
+use pathfinding_simulation_core::Topology;
+use serde::{Deserialize, Serialize};
+
+// The cells of a maze in one buffer, row by row, one byte per cell. Bit n of
+// a byte is wall n and the bit after the last wall marks the cell as visited
+// by the generator. A wall shared by two cells is stored only once, in the
+// cell that comes first row by row, so the two sides can never disagree.
+// Only cells on the edge of the grid use their own bits for the walls that
+// face outwards. Walls are numbered as in `Topology`.
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    topology: Topology,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// A grid with every wall standing and no cell visited.
+    pub fn new(width: usize, height: usize, topology: Topology) -> Self {
+        Self {
+            width,
+            height,
+            topology,
+            cells: vec![Self::all_walls(topology); width * height],
+        }
+    }
+
+    fn all_walls(topology: Topology) -> u8 {
+        (1 << topology.wall_count()) - 1
+    }
+
+    fn visited_bit(&self) -> u8 {
+        1 << self.topology.wall_count()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn wall_count(&self) -> usize {
+        self.topology.wall_count()
+    }
+
+    /// The cell on the other side of `wall` of (x, y), or None on the edge of the grid.
+    pub fn neighbor(&self, x: usize, y: usize, wall: usize) -> Option<(usize, usize)> {
+        let (dx, dy) = self.topology.offset(y, wall);
+        let nx = x.checked_add_signed(dx).filter(|&nx| nx < self.width)?;
+        let ny = y.checked_add_signed(dy).filter(|&ny| ny < self.height)?;
+        Some((nx, ny))
+    }
+
+    /// The wall of `from` that separates it from the neighbouring cell `to`.
+    pub fn wall_between(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        (0..self.wall_count())
+            .find(|&wall| self.neighbor(from.0, from.1, wall) == Some(to))
+            .expect("cells should be neighbours")
+    }
+
+    /// Every wall between two cells once, as (x, y, wall) of the cell that stores it.
+    pub fn interior_walls(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width).flat_map(move |x| {
+                (0..self.wall_count()).filter_map(move |wall| {
+                    let (nx, ny) = self.neighbor(x, y, wall)?;
+                    (ny * self.width + nx > y * self.width + x).then_some((x, y, wall))
+                })
+            })
+        })
+    }
+
+    /// Where a wall is stored: the byte and the bit within it.
+    fn slot(&self, x: usize, y: usize, wall: usize) -> (usize, u8) {
+        debug_assert!(x < self.width && y < self.height && wall < self.wall_count());
+        match self.neighbor(x, y, wall) {
+            Some((nx, ny)) if (ny, nx) < (y, x) => (ny * self.width + nx, 1 << self.topology.opposite(wall)),
+            _ => (y * self.width + x, 1 << wall),
+        }
+    }
+
+    pub fn wall(&self, x: usize, y: usize, wall: usize) -> bool {
+        let (index, bit) = self.slot(x, y, wall);
+        self.cells[index] & bit != 0
+    }
+
+    /// All walls of a cell, in wall order. Only the first `wall_count()` are
+    /// walls, the rest are always false.
+    pub fn walls(&self, x: usize, y: usize) -> [bool; MAX_WALLS] {
+        let mut walls = [false; MAX_WALLS];
+        for (wall, standing) in walls.iter_mut().enumerate().take(self.wall_count()) {
+            *standing = self.wall(x, y, wall);
+        }
+        walls
+    }
+
+    /// Raises or knocks down a wall. The cell on the other side sees the same
+    /// change, as the wall is shared.
+    pub fn set_wall(&mut self, x: usize, y: usize, wall: usize, standing: bool) {
+        let (index, bit) = self.slot(x, y, wall);
+        if standing {
+            self.cells[index] |= bit;
+        } else {
+            self.cells[index] &= !bit;
+        }
+    }
+
+    /// Raises or knocks down every wall of a cell, one flag per wall.
+    pub fn set_walls(&mut self, x: usize, y: usize, walls: &[bool]) {
+        for (wall, &standing) in walls.iter().enumerate() {
+            self.set_wall(x, y, wall, standing);
+        }
+    }
+
+    pub fn visited(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.width + x] & self.visited_bit() != 0
+    }
+
+    pub fn set_visited(&mut self, x: usize, y: usize) {
+        let bit = self.visited_bit();
+        self.cells[y * self.width + x] |= bit;
+    }
+
+    /// Puts every wall back up and forgets every visit.
+    pub fn reset(&mut self) {
+        self.cells.fill(Self::all_walls(self.topology));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_walls_are_stored_once() {
+        let mut grid = Grid::new(3, 2, Topology::Square);
+        grid.set_wall(1, 0, 1, false);
+        assert!(!grid.wall(2, 0, 3));
+        grid.set_wall(1, 1, 0, false);
+        assert!(!grid.wall(1, 0, 2));
+        assert_eq!(grid.walls(1, 0)[..4], [true, false, false, true]);
+
+        // Edge walls belong to the edge cells alone
+        grid.set_wall(0, 0, 0, false);
+        grid.set_wall(2, 1, 1, false);
+        assert_eq!(grid.walls(0, 0)[..4], [false, true, true, true]);
+        assert_eq!(grid.walls(2, 1)[..4], [true, false, true, true]);
+
+        grid.set_walls(1, 1, &[true; 4]);
+        assert!(grid.wall(1, 0, 2));
+    }
+
+    #[test]
+    fn test_visits_are_separate_from_walls() {
+        for topology in Topology::ALL {
+            let mut grid = Grid::new(2, 2, topology);
+            grid.set_visited(1, 1);
+            assert!(grid.visited(1, 1) && !grid.visited(0, 1));
+            assert!(grid.walls(1, 1)[..topology.wall_count()].iter().all(|&standing| standing));
+
+            grid.set_wall(0, 0, 1, false);
+            grid.reset();
+            assert!(!grid.visited(1, 1));
+            assert!(grid.wall(0, 0, 1));
+        }
+    }
+
+    #[test]
+    fn test_hex_neighbours_are_symmetric() {
+        let mut grid = Grid::new(5, 4, Topology::Hex);
+        // Even rows lean left and odd rows right on the diagonals
+        assert_eq!(grid.neighbor(2, 2, 0), Some((2, 1)));
+        assert_eq!(grid.neighbor(2, 2, 5), Some((1, 1)));
+        assert_eq!(grid.neighbor(2, 1, 0), Some((3, 0)));
+        assert_eq!(grid.neighbor(2, 1, 3), Some((2, 2)));
+        assert_eq!(grid.neighbor(0, 0, 5), None);
+
+        for y in 0..4 {
+            for x in 0..5 {
+                for wall in 0..6 {
+                    let Some((nx, ny)) = grid.neighbor(x, y, wall) else {
+                        continue;
+                    };
+                    assert_eq!(grid.neighbor(nx, ny, Topology::Hex.opposite(wall)), Some((x, y)));
+                    assert_eq!(Topology::Hex.distance((x, y), (nx, ny)), 1);
+
+                    grid.set_wall(x, y, wall, false);
+                    assert!(!grid.wall(nx, ny, Topology::Hex.opposite(wall)));
+                    grid.set_wall(nx, ny, Topology::Hex.opposite(wall), true);
+                    assert!(grid.wall(x, y, wall));
+                }
+            }
+        }
+
+        // 5 x 4 cells: 4 per row across, 3 x 3 pairs of rows with 2 diagonals per cell, minus the edges
+        assert_eq!(grid.interior_walls().count(), 4 * 4 + 3 * (2 * 5 - 1));
+        assert_eq!(Topology::Hex.distance((0, 0), (4, 3)), 6);
+    }
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+use super::Maze;
+
+/// Mazes with more cells than this don't record their generation. At about two
+/// events a cell and four bytes an event, that keeps the history under 32 MB.
+pub const HISTORY_CELL_LIMIT: usize = 1 << 22;
+
+/// Low bits of a packed event that mean a visit rather than a wall.
+const VISIT: u32 = 0b111;
+
+/// A single change made to the grid while generating, recorded so the
+/// generation can be replayed and scrubbed through afterwards.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum MazeEvent {
+    Visit { x: usize, y: usize },
+    RemoveWall { x: usize, y: usize, wall: usize }, // Also removes the neighbour's opposite wall
+}
+
+/// The events recorded so far, packed into four bytes each: the index of the
+/// cell shifted up by three bits, and below it the wall or `VISIT`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    width: usize,
+    recording: bool,
+    events: Vec<u32>,
+}
+
+impl History {
+    /// An empty history for a grid of the given size, recording only if the
+    /// grid is small enough.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            recording: width * height <= HISTORY_CELL_LIMIT,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn push(&mut self, event: MazeEvent) {
+        let (x, y, low) = match event {
+            MazeEvent::Visit { x, y } => (x, y, VISIT),
+            MazeEvent::RemoveWall { x, y, wall } => (x, y, wall as u32),
+        };
+        let index = (y * self.width + x) as u32;
+        self.events.push((index << 3) | low);
+    }
+
+    pub fn get(&self, index: usize) -> MazeEvent {
+        let packed = self.events[index];
+        let cell = (packed >> 3) as usize;
+        let (x, y) = (cell % self.width, cell / self.width);
+        match packed & VISIT {
+            VISIT => MazeEvent::Visit { x, y },
+            wall => MazeEvent::RemoveWall { x, y, wall: wall as usize },
+        }
+    }
+}
+
+impl Maze {
+    /// Applies an event to the grid and appends it to the history, if the
+    /// history is being recorded.
+    pub(super) fn record(&mut self, event: MazeEvent) {
+        self.apply(event);
+        if self.history.recording {
+            self.history.push(event);
+            self.history_cursor += 1;
+        }
+    }
+
+    /// Stops recording the generation and drops what was recorded so far, for
+    /// runs that are never scrubbed through. The grid catches up to the latest
+    /// event first.
+    pub fn stop_recording(&mut self) {
+        self.seek(self.history.len());
+        self.history.recording = false;
+        self.history.events = Vec::new();
+        self.history_cursor = 0;
+    }
+
+    fn apply(&mut self, event: MazeEvent) {
+        self.revision += 1;
+        match event {
+            MazeEvent::Visit { x, y } => self.grid.set_visited(x, y),
+            MazeEvent::RemoveWall { x, y, wall } => self.grid.set_wall(x, y, wall, false),
+        }
+    }
+
+    /// Whether the grid shows the latest recorded event, i.e. the timeline
+    /// has not been rewound.
+    pub fn is_live(&self) -> bool {
+        self.history_cursor == self.history.len()
+    }
+
+    /// Whether generation has run to completion and the grid shows the result.
+    pub fn is_generated(&self) -> bool {
+        self.generator.is_none() && self.is_live()
+    }
+
+    /// Moves the grid to the state it had after `target` recorded events.
+    /// Rewinding rebuilds the grid from scratch, so any solver is discarded.
+    pub fn seek(&mut self, target: usize) {
+        let target = target.min(self.history.len());
+
+        if target < self.history_cursor {
+            self.grid.reset();
+            self.revision += 1;
+            self.history_cursor = 0;
+            self.solver = None;
+            self.fleet = None;
+            self.distance_field = None;
+            self.diameter = None;
+        }
+
+        while self.history_cursor < target {
+            self.apply(self.history.get(self.history_cursor));
+            self.history_cursor += 1;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::app::maze::grid::MAX_WALLS;
+
+    fn walls(maze: &Maze) -> Vec<[bool; MAX_WALLS]> {
+        (0..maze.height)
+            .flat_map(|y| (0..maze.width).map(move |x| maze.grid.walls(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_seek_replays_generation() {
+        let mut maze = Maze::new(12, 9);
+        maze.init_prims();
+        let mut generation_time = Duration::ZERO;
+        while maze.step(50, &mut generation_time) {}
+
+        let finished = walls(&maze);
+        let events = maze.history.len();
+
+        maze.seek(0);
+        assert!((0..maze.height).all(|y| (0..maze.width).all(|x| !maze.grid.visited(x, y))));
+        assert!(!maze.is_generated());
+
+        maze.seek(events / 2);
+        assert_eq!(maze.history_cursor, events / 2);
+
+        // Playing on from the middle catches back up to the recorded result
+        while maze.step(50, &mut generation_time) {}
+        assert!(maze.is_generated());
+        assert_eq!(walls(&maze), finished);
+        assert_eq!(maze.history.len(), events);
+    }
+
+    #[test]
+    fn test_events_survive_packing() {
+        let mut history = History::new(7, 5);
+        let events = [
+            MazeEvent::Visit { x: 6, y: 4 },
+            MazeEvent::RemoveWall { x: 0, y: 3, wall: 5 },
+            MazeEvent::RemoveWall { x: 3, y: 0, wall: 0 },
+        ];
+        for event in events {
+            history.push(event);
+        }
+        assert_eq!((0..history.len()).map(|i| history.get(i)).collect::<Vec<_>>(), events);
+    }
+
+    #[test]
+    fn test_large_mazes_generate_without_history() {
+        // Too big to record by default, only the grid is allocated
+        let maze = Maze::new(2048, 2049);
+        assert!(!maze.history.is_recording());
+
+        let mut maze = Maze::new(300, 300);
+        maze.stop_recording();
+        maze.init_dfs();
+        let mut generation_time = Duration::ZERO;
+        while maze.step(10_000, &mut generation_time) {}
+
+        assert!(maze.is_generated());
+        assert!(maze.history.is_empty());
+        assert!(maze.validate().is_perfect());
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use super::solver::{CellMark, MazeSolver};
+use super::Maze;
+
+// Iterative deepening DFS and IDA* share the same loop: a depth first search
+// that refuses to go past a cost bound, restarted with a larger bound until it
+// reaches the goal. IDDFS bounds the path length, IDA* bounds g + h. Only the
+// current path is kept, so memory stays proportional to the path while cells
+// get expanded again on every iteration.
+
+/// A cell on the current path and which of its walls to try next.
+#[derive(Clone, Serialize, Deserialize)]
+struct Frame {
+    cell: usize,
+    cost: usize,
+    next_wall: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Deepening {
+    pub bound: f64,
+    next_bound: Option<f64>, // Smallest priority that was cut off by the current bound
+    path: Vec<Frame>,
+    pub iteration: usize,
+}
+
+impl Deepening {
+    pub(super) fn new(bound: f64) -> Self {
+        Self {
+            bound,
+            next_bound: None,
+            path: Vec::new(),
+            iteration: 0,
+        }
+    }
+}
+
+impl Maze {
+    /// Takes one step of the depth first search under the current bound, starting
+    /// the next iteration when the bound is exhausted. Returns false once a path
+    /// has been found or no cell was cut off, meaning the goal is unreachable.
+    pub(super) fn expand_deepening(&self, solver: &mut MazeSolver) -> bool {
+        let mut deepening = solver.deepening.take().expect("iterative deepening solver without its state");
+        let still_running = self.deepen(solver, &mut deepening);
+        solver.deepening = Some(deepening);
+        still_running
+    }
+
+    fn deepen(&self, solver: &mut MazeSolver, deepening: &mut Deepening) -> bool {
+        loop {
+            let Some(frame) = deepening.path.last_mut() else {
+                // Either the very first iteration or the previous bound was exhausted
+                if deepening.iteration > 0 {
+                    let Some(next_bound) = deepening.next_bound.take() else {
+                        return false; // Nothing was cut off, every reachable cell has been tried
+                    };
+                    deepening.bound = next_bound;
+                }
+                deepening.iteration += 1;
+                solver.forward.marks.fill(CellMark::Unseen);
+
+                let (x, y) = solver.forward.origin;
+                let start = y * self.width + x;
+                return self.enter(solver, deepening, start, 0, None);
+            };
+
+            if frame.next_wall == self.grid.wall_count() {
+                // Every direction tried, backtrack
+                solver.forward.marks[frame.cell] = CellMark::Closed;
+                deepening.path.pop();
+                continue;
+            }
+
+            let (cell, cost) = (frame.cell, frame.cost);
+            let wall = frame.next_wall;
+            frame.next_wall += 1;
+
+            let Some((nx, ny)) = self.passage((cell % self.width, cell / self.width), wall) else {
+                continue;
+            };
+            let neighbor = ny * self.width + nx;
+            if solver.forward.marks[neighbor] == CellMark::Open {
+                continue; // Already on the current path
+            }
+
+            let priority = solver.priority(&solver.forward, cost + 1, (nx, ny));
+            if priority > deepening.bound {
+                deepening.next_bound = Some(deepening.next_bound.map_or(priority, |bound| bound.min(priority)));
+                continue;
+            }
+
+            return self.enter(solver, deepening, neighbor, cost + 1, Some(cell));
+        }
+    }
+
+    /// Pushes `cell` onto the current path. Returns false if it is the goal.
+    fn enter(
+        &self,
+        solver: &mut MazeSolver,
+        deepening: &mut Deepening,
+        cell: usize,
+        cost: usize,
+        parent: Option<usize>,
+    ) -> bool {
+        let search = &mut solver.forward;
+        search.marks[cell] = CellMark::Open;
+        search.came_from[cell] = parent;
+        search.cost[cell] = cost;
+        search.expanded += 1;
+
+        deepening.path.push(Frame { cell, cost, next_wall: 0 });
+        search.peak_memory = search.peak_memory.max(deepening.path.len());
+
+        if (cell % self.width, cell / self.width) == search.target {
+            let path = deepening.path.iter().map(|frame| frame.cell).collect();
+            self.set_path(solver, path);
+            return false;
+        }
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::app::algorithms::PathfindingAlgorithms;
+    use crate::app::maze::{generated, solve, solve_with, SolverOptions, Topology};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    #[test]
+    fn test_iterative_deepening_finds_shortest_path() {
+        let maze = generated(12, 10, MazeAlgorithms::Prims, 0.1);
+
+        let optimal = maze.shortest_path_cost((0, 0), (11, 9));
+        for algorithm in [PathfindingAlgorithms::IterativeDeepeningDfs, PathfindingAlgorithms::IdaStar] {
+            let mut maze = maze.clone();
+            assert_eq!(solve(&mut maze, algorithm), optimal, "{}", algorithm);
+
+            let path = maze.solver.as_ref().unwrap().path.clone().unwrap();
+            for pair in path.windows(2) {
+                let (x, y) = pair[0];
+                assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_trades_time_for_memory() {
+        let maze = generated(20, 20, MazeAlgorithms::Dfs, 0.0);
+
+        let mut astar = maze.clone();
+        let mut ida = maze.clone();
+        let cost = solve(&mut astar, PathfindingAlgorithms::Astar).unwrap();
+        assert_eq!(solve(&mut ida, PathfindingAlgorithms::IdaStar), Some(cost));
+
+        let astar = astar.solver.unwrap();
+        let ida = ida.solver.unwrap();
+        assert!(ida.expanded() >= astar.expanded());
+        assert_eq!(ida.peak_memory(), cost + 1); // Never holds more than the path itself
+    }
+
+    #[test]
+    fn test_iterative_deepening_unreachable_goal() {
+        let mut maze = Maze::new(4, 4);
+        assert_eq!(solve(&mut maze, PathfindingAlgorithms::IterativeDeepeningDfs), None);
+        assert!(maze.solver.as_ref().unwrap().finished);
+    }
+
+    #[test]
+    fn test_iterative_deepening_moves_west_on_hex_cells() {
+        // The goal is straight west, so only walls 4 and 5 lead anywhere
+        let mut maze = Maze::with_topology(4, 1, Topology::Hex);
+        for x in 0..maze.width - 1 {
+            maze.grid.set_wall(x, 0, 1, false);
+        }
+
+        for algorithm in [PathfindingAlgorithms::IterativeDeepeningDfs, PathfindingAlgorithms::IdaStar] {
+            let mut maze = maze.clone();
+            let cost = solve_with(&mut maze, algorithm, SolverOptions::default(), (3, 0), (0, 0));
+            assert_eq!(cost, Some(3), "{}", algorithm);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::solver::{CellMark, MazeSolver, Search};
+use super::Maze;
+
+// Jump Point Search adapted to a 4-connected grid with walls between cells.
+//
+// Among equally short paths only the ones that move vertically as early as
+// possible are considered. Horizontal moves therefore never turn unless the
+// turn is forced by a wall, while vertical moves may turn sideways at every
+// cell. Cells that only continue a straight line are skipped over, so the
+// open list only ever holds the jump points where a path can change direction.
+// The rules only hold for square cells, so on hex mazes the solver runs JPS
+// as plain A*.
+
+const UP: usize = 0;
+const RIGHT: usize = 1;
+const DOWN: usize = 2;
+const LEFT: usize = 3;
+
+impl Maze {
+    /// Closes the jump point `current` and pushes the jump points reachable from it.
+    /// Returns the cell if it is the goal.
+    pub(super) fn expand_jump_point(&self, solver: &mut MazeSolver, current: usize) -> Option<usize> {
+        let position = (current % self.width, current / self.width);
+
+        let search = &mut solver.forward;
+        search.marks[current] = CellMark::Closed;
+        search.expanded += 1;
+
+        if position == search.target {
+            return Some(current);
+        }
+
+        let mut successors = Vec::new();
+        for direction in self.pruned_directions(search, current) {
+            if let Some(jump_point) = self.jump(search, position, direction) {
+                successors.push(jump_point);
+            }
+        }
+
+        for (x, y) in successors {
+            let cell = y * self.width + x;
+            if solver.forward.marks[cell] == CellMark::Closed {
+                continue;
+            }
+
+            let distance = x.abs_diff(position.0) + y.abs_diff(position.1);
+            let new_cost = solver.forward.cost[current] + distance;
+            if new_cost < solver.forward.cost[cell] {
+                let priority = solver.priority(&solver.forward, new_cost, (x, y));
+                let search = &mut solver.forward;
+                search.cost[cell] = new_cost;
+                search.came_from[cell] = Some(current);
+                search.push(cell, priority);
+            }
+        }
+        None
+    }
+
+    /// Directions worth searching from a jump point, given how it was reached.
+    fn pruned_directions(&self, search: &Search, current: usize) -> Vec<usize> {
+        let Some(parent) = search.came_from[current] else {
+            return vec![UP, RIGHT, DOWN, LEFT]; // The start has no parent to prune with
+        };
+
+        let position = (current % self.width, current / self.width);
+        let direction = if parent / self.width == current / self.width {
+            if parent < current { RIGHT } else { LEFT }
+        } else if parent < current {
+            DOWN
+        } else {
+            UP
+        };
+
+        if direction == UP || direction == DOWN {
+            return vec![direction, LEFT, RIGHT];
+        }
+
+        // Horizontal moves only turn where a wall forces them to
+        let previous = self.step_back(position, direction);
+        let mut directions = vec![direction];
+        for turn in [UP, DOWN] {
+            if self.is_forced(previous, position, direction, turn) {
+                directions.push(turn);
+            }
+        }
+        directions
+    }
+
+    /// Scans from `from` in `direction` until reaching a jump point, or None
+    /// if the scan runs into a wall first.
+    fn jump(&self, search: &mut Search, from: (usize, usize), direction: usize) -> Option<(usize, usize)> {
+        let mut previous = from;
+        loop {
+            let next = self.passage(previous, direction)?;
+            let index = next.1 * self.width + next.0;
+            if search.marks[index] == CellMark::Unseen {
+                search.marks[index] = CellMark::Scanned;
+            }
+
+            if next == search.target {
+                return Some(next);
+            }
+
+            if direction == LEFT || direction == RIGHT {
+                if [UP, DOWN]
+                    .into_iter()
+                    .any(|turn| self.is_forced(previous, next, direction, turn))
+                {
+                    return Some(next);
+                }
+            } else if self.jump(search, next, LEFT).is_some() || self.jump(search, next, RIGHT).is_some() {
+                // A vertical scan stops wherever a sideways scan would find something
+                return Some(next);
+            }
+
+            previous = next;
+        }
+    }
+
+    /// Whether turning `turn` at `current`, after moving `direction` from `previous`,
+    /// is forced: the equally long route that turns at `previous` first is walled off.
+    fn is_forced(&self, previous: (usize, usize), current: (usize, usize), direction: usize, turn: usize) -> bool {
+        let Some(target) = self.passage(current, turn) else {
+            return false;
+        };
+        match self.passage(previous, turn) {
+            Some(beside) => self.passage(beside, direction) != Some(target),
+            None => true,
+        }
+    }
+
+    fn step_back(&self, (x, y): (usize, usize), direction: usize) -> (usize, usize) {
+        match direction {
+            UP => (x, y + 1),
+            RIGHT => (x - 1, y),
+            DOWN => (x, y - 1),
+            _ => (x + 1, y),
+        }
+    }
+
+    /// Expands a chain of jump points into every cell along the way.
+    pub(super) fn fill_jumps(&self, jump_points: Vec<usize>) -> Vec<usize> {
+        let mut path = Vec::new();
+        for pair in jump_points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let step = if from / self.width == to / self.width { 1 } else { self.width };
+
+            let mut cell = from;
+            while cell != to {
+                path.push(cell);
+                cell = if to > cell { cell + step } else { cell - step };
+            }
+        }
+        path.extend(jump_points.last());
+        path
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::algorithms::PathfindingAlgorithms;
+    use crate::app::maze::{generated, solve};
+    use crate::app::MazeAlgorithms;
+
+    #[test]
+    fn test_jump_point_search_is_optimal_on_braided_mazes() {
+        for fraction in [0.0, 0.1, 0.3, 0.6, 1.0] {
+            for _ in 0..10 {
+                let mut maze = generated(24, 17, MazeAlgorithms::Dfs, fraction);
+
+                let optimal = maze.shortest_path_cost((0, 0), (23, 16));
+                assert_eq!(solve(&mut maze, PathfindingAlgorithms::JumpPointSearch), optimal);
+
+                let path = maze.solver.as_ref().unwrap().path.clone().unwrap();
+                assert_eq!(path.first(), Some(&(0, 0)));
+                assert_eq!(path.last(), Some(&(23, 16)));
+                for pair in path.windows(2) {
+                    let (x, y) = pair[0];
+                    assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_jump_point_search_expands_less_than_astar_on_open_grid() {
+        let mut maze = Maze::new(40, 40);
+        maze.braid(1.0);
+
+        let mut astar = maze.clone();
+        let mut jps = maze.clone();
+        assert_eq!(solve(&mut astar, PathfindingAlgorithms::Astar), Some(78));
+        assert_eq!(solve(&mut jps, PathfindingAlgorithms::JumpPointSearch), Some(78));
+
+        let expanded = |maze: &Maze| maze.solver.as_ref().unwrap().expanded();
+        assert!(expanded(&jps) * 10 < expanded(&astar), "{} vs {}", expanded(&jps), expanded(&astar));
+    }
+}
@@ -1,12 +1,39 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use rand::rngs::StdRng;
 use rand::seq::{IteratorRandom, SliceRandom};
-use rand::{thread_rng, Rng};
-pub mod node;
-
-pub use node::Node;
-
-
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::app::MazeAlgorithms;
+mod agents;
+mod analysis;
+mod d_star_lite;
+mod distance_field;
+pub mod dynamic_walls;
+pub mod grid;
+pub mod history;
+mod iterative_deepening;
+mod jump_point_search;
+mod multi_agent;
+pub mod solver;
+#[cfg(test)]
+mod uniformity;
+mod validate;
+
+pub use analysis::MazeStats;
+pub use distance_field::{Diameter, DistanceField};
+pub use dynamic_walls::{DoorMode, DynamicWalls, WallChange};
+pub use grid::Grid;
+pub use history::{History, MazeEvent};
+pub use multi_agent::Fleet;
+pub use solver::{CellMark, MazeSolver, SolverOptions};
+pub use validate::Validation;
+pub use pathfinding_simulation_core::{DisjointSet, Topology};
+
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum MazeGenerator {
     Dfs {
         stack: Vec<(usize, usize)>,
@@ -20,48 +47,132 @@ pub enum MazeGenerator {
     },
     Kruskal {
         edges: Vec<(usize, usize, usize, usize)>, // All edges (walls)
-        sets: Vec<usize>, // Union-Find data structure to track connected regions
+        sets: DisjointSet, // Union-Find data structure to track connected regions
+        #[serde(default)]
+        candidate: Option<WallChange>, // The edge looked at last, open if its wall came down
     },
 
     // You can add other algorithms here
 }
 
+// Only the size and the packed grid are saved with the session. Everything
+// else is derived from the grid or only matters while the app is running, and
+// would make every autosave far bigger than the grid itself.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Maze {
     pub width: usize,
     pub height: usize,
-    pub grid: Vec<Vec<Node>>,
+    pub grid: Grid,
+    #[serde(skip)]
     pub generator: Option<MazeGenerator>,
+    #[serde(skip)]
+    pub solver: Option<MazeSolver>,
+    #[serde(skip)]
+    pub history: History, // Every change made while generating, unless the maze is too big
+    #[serde(skip)]
+    pub history_cursor: usize, // Number of history events currently applied to the grid
+    #[serde(skip)]
+    pub dynamic_walls: Option<DynamicWalls>, // Doors that open and close while solving
+    #[serde(skip)]
+    pub fleet: Option<Fleet>, // Several agents sharing the maze, instead of a single solver
+    #[serde(skip)]
+    pub distance_field: Option<DistanceField>, // Flood shown as a heatmap with its flow field
+    #[serde(skip)]
+    pub diameter: Option<Diameter>, // Suggested start and goal, as far apart as the maze allows
+    #[serde(skip)]
+    revision: usize, // Counts changes to the grid, so results measured on it know when they are stale
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng, // Every random choice the generators make, seedable for reproducible runs
 }
 
 impl Maze {
     pub fn new(width: usize, height: usize) -> Self {
-        let grid = (0..height)
-            .map(|y| {
-                (0..width)
-                    .map(|x| Node {
-                        x,
-                        y,
-                        visited: false,
-                        walls: [true, true, true, true],
-                    })
-                    .collect()
-            })
-            .collect();
+        Self::with_topology(width, height, Topology::Square)
+    }
 
+    /// An empty maze whose cells have the shape `topology` gives them.
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
         Maze {
             width,
             height,
-            grid,
+            grid: Grid::new(width, height, topology),
             generator: None,
+            solver: None,
+            history: History::new(width, height),
+            history_cursor: 0,
+            dynamic_walls: None,
+            fleet: None,
+            distance_field: None,
+            diameter: None,
+            revision: 0,
+            rng: StdRng::from_entropy(),
         }
     }
 
+    /// Changes whenever a wall or visit in the grid does. Anything computed from
+    /// the grid is still up to date as long as this is the same.
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Makes the generator's random choices repeatable: the same seed, size and
+    /// algorithm always produce the same maze.
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Prepares step-wise generation with the chosen algorithm.
+    pub fn init_generator(&mut self, algorithm: MazeAlgorithms) {
+        match algorithm {
+            MazeAlgorithms::Dfs => self.init_dfs(),
+            MazeAlgorithms::Prims => self.init_prims(),
+            MazeAlgorithms::Kruskals => self.init_kruskals(),
+            MazeAlgorithms::AldousBroder => self.init_aldous_broder(),
+        }
+    }
+
+    /// The cell reached by leaving (x, y) through `wall`, numbered as in
+    /// `Grid`, or None if that wall is standing or leads off the grid.
+    pub fn passage(&self, (x, y): (usize, usize), wall: usize) -> Option<(usize, usize)> {
+        if self.grid.wall(x, y, wall) {
+            return None;
+        }
+        self.grid.neighbor(x, y, wall)
+    }
+
+    /// The cells next to (x, y), walls or not, each with the wall between them.
+    fn neighbors(&self, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        (0..self.grid.wall_count())
+            .filter_map(move |wall| self.grid.neighbor(x, y, wall).map(|(nx, ny)| (nx, ny, wall)))
+    }
+
+    /// Removes a random share of the interior walls still standing, adding
+    /// loops and open areas to a generated maze.
+    pub fn braid(&mut self, fraction: f32) {
+        let interior: Vec<(usize, usize, usize)> = self.grid.interior_walls().collect();
+        for (x, y, wall) in interior {
+            if self.grid.wall(x, y, wall) && self.rng.gen::<f32>() < fraction {
+                self.record(MazeEvent::RemoveWall { x, y, wall });
+            }
+        }
+        self.solver = None;
+        self.fleet = None;
+        self.distance_field = None;
+        self.diameter = None;
+    }
+
+    /// Neighbouring cells that can be reached from (x, y) without crossing a wall.
+    pub fn open_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        (0..self.grid.wall_count())
+            .filter_map(|wall| self.passage((x, y), wall))
+            .collect()
+    }
+
     /// Initializes the maze for Dfs algorithm.
     pub fn init_dfs(&mut self) {
-        let mut rng = thread_rng();
-        let start_x = rng.gen_range(0..self.width);
-        let start_y = rng.gen_range(0..self.height);
-        self.grid[start_y][start_x].visited = true;
+        let start_x = self.rng.gen_range(0..self.width);
+        let start_y = self.rng.gen_range(0..self.height);
+        self.record(MazeEvent::Visit { x: start_x, y: start_y });
 
         self.generator = Some(MazeGenerator::Dfs {
             stack: vec![(start_x, start_y)],
@@ -69,35 +180,24 @@ impl Maze {
     }
 
     pub fn init_prims(&mut self) {
-        let mut rng = thread_rng();
-        let start_x = rng.gen_range(0..self.width);
-        let start_y = rng.gen_range(0..self.height);
-        self.grid[start_y][start_x].visited = true;
+        let start_x = self.rng.gen_range(0..self.width);
+        let start_y = self.rng.gen_range(0..self.height);
+        self.record(MazeEvent::Visit { x: start_x, y: start_y });
 
-        let mut walls = Vec::new();
         // Add initial walls surrounding the starting cell
-        if start_x > 0 {
-            walls.push((start_x, start_y, start_x - 1, start_y)); // Left wall
-        }
-        if start_x < self.width - 1 {
-            walls.push((start_x, start_y, start_x + 1, start_y)); // Right wall
-        }
-        if start_y > 0 {
-            walls.push((start_x, start_y, start_x, start_y - 1)); // Top wall
-        }
-        if start_y < self.height - 1 {
-            walls.push((start_x, start_y, start_x, start_y + 1)); // Bottom wall
-        }
+        let walls = self
+            .neighbors((start_x, start_y))
+            .map(|(nx, ny, _)| (start_x, start_y, nx, ny))
+            .collect();
 
         self.generator = Some(MazeGenerator::Prims { walls });
     }
 
     pub fn init_aldous_broder(&mut self){
 
-        let mut rng = thread_rng();
-        let start_x = rng.gen_range(0..self.width);
-        let start_y = rng.gen_range(0..self.height);
-        self.grid[start_y][start_x].visited = true;
+        let start_x = self.rng.gen_range(0..self.width);
+        let start_y = self.rng.gen_range(0..self.height);
+        self.record(MazeEvent::Visit { x: start_x, y: start_y });
 
         self.generator = Some(MazeGenerator::AldousBroder { 
             current: (start_x,start_y), 
@@ -107,38 +207,34 @@ impl Maze {
     }
 
     pub fn init_kruskals(&mut self) {
-        let mut edges = Vec::new();
-        let mut sets = vec![0; self.width * self.height];
-    
-        // Initialize Union-Find sets
-        for i in 0..sets.len() {
-            sets[i] = i;
-        }
+        let sets = DisjointSet::new(self.width * self.height);
     
         // Collect all possible edges (walls) between cells
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if x < self.width - 1 {
-                    // Right wall
-                    edges.push((x, y, x + 1, y));
-                }
-                if y < self.height - 1 {
-                    // Bottom wall
-                    edges.push((x, y, x, y + 1));
-                }
-            }
-        }
+        let mut edges: Vec<(usize, usize, usize, usize)> = self
+            .grid
+            .interior_walls()
+            .map(|(x, y, wall)| {
+                let (nx, ny) = self.grid.neighbor(x, y, wall).unwrap();
+                (x, y, nx, ny)
+            })
+            .collect();
     
         // Shuffle edges for randomness
-        edges.shuffle(&mut thread_rng());
+        edges.shuffle(&mut self.rng);
         
         // Initialize the generator
-        self.generator = Some(MazeGenerator::Kruskal { edges, sets });
+        self.generator = Some(MazeGenerator::Kruskal { edges, sets, candidate: None });
     }
     
         pub fn step(&mut self, steps: usize, generation_time: &mut Duration) -> bool {
             
             let start = Instant::now(); // Start timing all steps
+
+            // Play back recorded events first if the timeline has been rewound
+            if !self.is_live() {
+                self.seek(self.history_cursor + steps);
+                return true;
+            }
         
             for _ in 0..steps {
                 // Take the generator out of self.generator
@@ -152,7 +248,7 @@ impl Maze {
                     MazeGenerator::Dfs { stack } => !self.dfs_step(stack),
                     MazeGenerator::Prims { walls } => !self.prims_step(walls),
                     MazeGenerator::AldousBroder { current, unvisited } => !self.aldous_broder_step(current, unvisited),
-                    MazeGenerator::Kruskal { edges, sets } => !self.kruskal_step(edges,sets),
+                    MazeGenerator::Kruskal { edges, sets, candidate } => !self.kruskal_step(edges, sets, candidate),
                 };
         
                 if generation_complete {
@@ -175,28 +271,16 @@ impl Maze {
 
         if let Some((x, y)) = stack.pop() {
             // Same logic as before but for a single step
-            let mut neighbors = Vec::new();
-
-            if x > 0 && !self.grid[y][x - 1].visited {
-                neighbors.push((x - 1, y, 3, 1));
-            }
-            if x < self.width - 1 && !self.grid[y][x + 1].visited {
-                neighbors.push((x + 1, y, 1, 3));
-            }
-            if y > 0 && !self.grid[y - 1][x].visited {
-                neighbors.push((x, y - 1, 0, 2));
-            }
-            if y < self.height - 1 && !self.grid[y + 1][x].visited {
-                neighbors.push((x, y + 1, 2, 0));
-            }
+            let neighbors: Vec<(usize, usize, usize)> = self
+                .neighbors((x, y))
+                .filter(|&(nx, ny, _)| !self.grid.visited(nx, ny))
+                .collect();
 
             if !neighbors.is_empty() {
-                let mut rng = thread_rng();
-                let &(nx, ny, current_wall, neighbor_wall) = neighbors.choose(&mut rng).unwrap();
+                let &(nx, ny, current_wall) = neighbors.choose(&mut self.rng).unwrap();
 
-                self.grid[y][x].walls[current_wall] = false;
-                self.grid[ny][nx].walls[neighbor_wall] = false;
-                self.grid[ny][nx].visited = true;
+                self.record(MazeEvent::RemoveWall { x, y, wall: current_wall });
+                self.record(MazeEvent::Visit { x: nx, y: ny });
 
                 stack.push((x, y));
                 stack.push((nx, ny));
@@ -209,43 +293,22 @@ impl Maze {
 
     fn prims_step(&mut self, walls: &mut Vec<(usize, usize, usize, usize)>) -> bool {
 
-        if let Some(index) = (0..walls.len()).choose(&mut thread_rng()) {
-            let (x1, y1, x2, y2) = walls.remove(index);
-
-            if !self.grid[y2][x2].visited {
-                self.grid[y2][x2].visited = true;
-
-                if x1 == x2 {
-                    if y1 > y2 {
-                        self.grid[y1][x1].walls[0] = false; // Top wall
-                        self.grid[y2][x2].walls[2] = false; // Bottom wall
-                    } else {
-                        self.grid[y1][x1].walls[2] = false; // Bottom wall
-                        self.grid[y2][x2].walls[0] = false; // Top wall
-                    }
-                } else {
-                    if x1 > x2 {
-                        self.grid[y1][x1].walls[3] = false; // Left wall
-                        self.grid[y2][x2].walls[1] = false; // Right wall
-                    } else {
-                        self.grid[y1][x1].walls[1] = false; // Right wall
-                        self.grid[y2][x2].walls[3] = false; // Left wall
-                    }
-                }
+        if let Some(index) = (0..walls.len()).choose(&mut self.rng) {
+            // The frontier is picked from at random, so its order doesn't matter
+            // and the last wall can fill the gap instead of shifting the rest
+            let (x1, y1, x2, y2) = walls.swap_remove(index);
+
+            if !self.grid.visited(x2, y2) {
+                self.record(MazeEvent::Visit { x: x2, y: y2 });
+                let wall = self.grid.wall_between((x1, y1), (x2, y2));
+                self.record(MazeEvent::RemoveWall { x: x1, y: y1, wall });
 
                 // Add neighboring walls
-                if x2 > 0 && !self.grid[y2][x2 - 1].visited {
-                    walls.push((x2, y2, x2 - 1, y2));
-                }
-                if x2 < self.width - 1 && !self.grid[y2][x2 + 1].visited {
-                    walls.push((x2, y2, x2 + 1, y2));
-                }
-                if y2 > 0 && !self.grid[y2 - 1][x2].visited {
-                    walls.push((x2, y2, x2, y2 - 1));
-                }
-                if y2 < self.height - 1 && !self.grid[y2 + 1][x2].visited {
-                    walls.push((x2, y2, x2, y2 + 1));
-                }
+                walls.extend(
+                    self.neighbors((x2, y2))
+                        .filter(|&(nx, ny, _)| !self.grid.visited(nx, ny))
+                        .map(|(nx, ny, _)| (x2, y2, nx, ny)),
+                );
             }
             true
         } else {
@@ -254,19 +317,10 @@ impl Maze {
     }
 
     fn aldous_broder_step(&mut self,current: &mut (usize, usize),unvisited: &mut usize,) -> bool {
-        let mut rng = thread_rng();
         let (x, y) = *current;
     
         // Randomly pick a valid neighbor
-        let neighbors: Vec<(usize, usize, usize, usize)> = vec![
-            (x.wrapping_sub(1), y, 3, 1), // Left
-            (x + 1, y, 1, 3),            // Right
-            (x, y.wrapping_sub(1), 0, 2), // Up
-            (x, y + 1, 2, 0),            // Down
-        ]
-        .into_iter()
-        .filter(|&(nx, ny, _, _)| nx < self.width && ny < self.height) // Keep within bounds
-        .collect();
+        let neighbors: Vec<(usize, usize, usize)> = self.neighbors((x, y)).collect();
     
         // If no neighbors, return early
         if neighbors.is_empty() {
@@ -274,12 +328,11 @@ impl Maze {
         }
     
         // Choose a random neighbor
-        if let Some(&(nx, ny, current_wall, neighbor_wall)) = neighbors.choose(&mut rng) {
-            if !self.grid[ny][nx].visited {
+        if let Some(&(nx, ny, current_wall)) = neighbors.choose(&mut self.rng) {
+            if !self.grid.visited(nx, ny) {
                 // Carve passage
-                self.grid[y][x].walls[current_wall] = false;
-                self.grid[ny][nx].walls[neighbor_wall] = false;
-                self.grid[ny][nx].visited = true;
+                self.record(MazeEvent::RemoveWall { x, y, wall: current_wall });
+                self.record(MazeEvent::Visit { x: nx, y: ny });
     
                 // Decrement unvisited count
                 *unvisited -= 1;
@@ -292,64 +345,110 @@ impl Maze {
         *unvisited > 0
     }
     
-    fn kruskal_step(&mut self, edges: &mut Vec<(usize, usize, usize, usize)>, sets: &mut Vec<usize>) -> bool {
-        while let Some((x1, y1, x2, y2)) = edges.pop() {
-            let index1 = y1 * self.width + x1; // Flattened index
-            let index2 = y2 * self.width + x2;
-
-            self.grid[y1][x1].visited = true;
-            self.grid[y2][x2].visited = true;
-    
-            if self.find(sets, index1) != self.find(sets, index2) {
-                // Merge the sets
-                self.union(sets, index1, index2);
-    
-                // Remove the wall between the cells
-                if x1 == x2 {
-                    // Vertical wall
-                    if y1 < y2 {
-                        self.grid[y1][x1].walls[2] = false; // Bottom
-                        self.grid[y2][x2].walls[0] = false; // Top
-                    } else {
-                        self.grid[y1][x1].walls[0] = false; // Top
-                        self.grid[y2][x2].walls[2] = false; // Bottom
-                    }
-                } else {
-                    // Horizontal wall
-                    if x1 < x2 {
-                        self.grid[y1][x1].walls[1] = false; // Right
-                        self.grid[y2][x2].walls[3] = false; // Left
-                    } else {
-                        self.grid[y1][x1].walls[3] = false; // Left
-                        self.grid[y2][x2].walls[1] = false; // Right
-                    }
+    /// Looks at one edge per step, so the animation shows the rejected ones
+    /// as well as the ones that join two sets.
+    fn kruskal_step(
+        &mut self,
+        edges: &mut Vec<(usize, usize, usize, usize)>,
+        sets: &mut DisjointSet,
+        candidate: &mut Option<WallChange>,
+    ) -> bool {
+        let Some((x1, y1, x2, y2)) = edges.pop() else {
+            *candidate = None;
+            return false; // No edges left, generation complete
+        };
+        let index1 = y1 * self.width + x1; // Flattened index
+        let index2 = y2 * self.width + x2;
+        let wall = self.grid.wall_between((x1, y1), (x2, y2));
+
+        // Merge the sets, unless the cells are already connected
+        let open = sets.union(index1, index2);
+        if open {
+            // Cells count as visited once they are joined to something
+            for (x, y) in [(x1, y1), (x2, y2)] {
+                if !self.grid.visited(x, y) {
+                    self.record(MazeEvent::Visit { x, y });
                 }
-                return true; // Continue generating
+            }
+            self.record(MazeEvent::RemoveWall { x: x1, y: y1, wall });
+
+            // Once everything is one set, the remaining edges would all be rejected
+            if sets.set_size(index1) == self.width * self.height {
+                edges.clear();
             }
         }
-        false // No edges left, generation complete
+        *candidate = Some(WallChange { x: x1, y: y1, wall, open });
+        true
     }
     
 }
 
+/// A maze fully generated with `generator`, then braided by `braid`.
+#[cfg(test)]
+pub(crate) fn generated(width: usize, height: usize, generator: MazeAlgorithms, braid: f32) -> Maze {
+    let mut maze = Maze::new(width, height);
+    maze.init_generator(generator);
+    let mut generation_time = Duration::ZERO;
+    while maze.step(1000, &mut generation_time) {}
+    maze.braid(braid);
+    maze
+}
 
+/// Runs `algorithm` from the top left to the bottom right cell and returns the cost of its path.
+#[cfg(test)]
+pub(crate) fn solve(maze: &mut Maze, algorithm: crate::app::PathfindingAlgorithms) -> Option<usize> {
+    let goal = (maze.width - 1, maze.height - 1);
+    solve_with(maze, algorithm, SolverOptions::default(), (0, 0), goal)
+}
 
+/// Runs `algorithm` with `options` between two cells and returns the cost of its path.
+#[cfg(test)]
+pub(crate) fn solve_with(
+    maze: &mut Maze,
+    algorithm: crate::app::PathfindingAlgorithms,
+    options: SolverOptions,
+    start: (usize, usize),
+    goal: (usize, usize),
+) -> Option<usize> {
+    maze.init_solver(algorithm, options, start, goal);
+    let mut solve_time = Duration::ZERO;
+    while maze.solve_step(1000, &mut solve_time) {}
+    maze.solver.as_ref().unwrap().path_cost()
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kruskal_looks_at_one_edge_per_step() {
+        let mut maze = Maze::new(6, 5);
+        maze.seed(3);
+        maze.init_kruskals();
+        let mut generation_time = Duration::ZERO;
+        let (mut accepted, mut rejected) = (0, 0);
+
+        while maze.step(1, &mut generation_time) {
+            let Some(MazeGenerator::Kruskal { candidate: Some(candidate), .. }) = &maze.generator else {
+                panic!("every step should leave the edge it looked at");
+            };
+            if candidate.open {
+                accepted += 1;
+            } else {
+                rejected += 1;
+            }
+            assert_eq!(maze.passage((candidate.x, candidate.y), candidate.wall).is_some(), candidate.open);
 
-
-impl Maze {
-    fn find(&mut self, sets: &mut [usize], node: usize) -> usize {
-        if sets[node] != node {
-            sets[node] = self.find(sets, sets[node]); // Path compression
+            // Only cells joined to a neighbour count as visited
+            for y in 0..maze.height {
+                for x in 0..maze.width {
+                    assert_eq!(maze.grid.visited(x, y), !maze.open_neighbors(x, y).is_empty());
+                }
+            }
         }
-        sets[node]
-    }
 
-    fn union(&mut self, sets: &mut [usize], a: usize, b: usize) {
-        let root_a = self.find(sets, a);
-        let root_b = self.find(sets, b);
-        if root_a != root_b {
-            sets[root_b] = root_a; // Merge the two sets
-        }
+        assert_eq!(accepted, 6 * 5 - 1);
+        assert!(rejected > 0);
+        assert!(maze.validate().is_perfect());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,319 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use super::Maze;
+
+// Cooperative A*: agents plan one after another in space-time, each treating
+// the cells and moves of the agents planned before it as reserved. An agent
+// that has arrived stays on its goal for good. Prioritised planning is not
+// complete, so when an order gets stuck a few other orders are tried, and
+// after that the fleet gives up rather than moving only some of its agents.
+
+/// Priority orders tried before giving up on a collision-free plan.
+const ATTEMPTS: usize = 8;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FleetAgent {
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    pub path: Vec<(usize, usize)>, // Cell at every timestep until the agent arrives for good
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Fleet {
+    pub agents: Vec<FleetAgent>,
+    pub time: usize, // Timestep being shown
+    pub planned: bool,
+    pub solved: bool, // Whether every agent got a collision-free path
+    pub attempts: usize,
+}
+
+/// Space-time cells and moves claimed by the agents planned so far.
+#[derive(Default)]
+struct Reservations {
+    cells: HashSet<(usize, usize)>,        // (cell, time)
+    moves: HashSet<(usize, usize, usize)>, // (from, to, time) for a move made between time and time + 1
+    parked: HashMap<usize, usize>,         // Goal cell and the time its agent arrives there for good
+    last_visit: HashMap<usize, usize>,     // Latest time each cell is occupied on the way somewhere
+    until: usize,                          // Longest path reserved so far
+}
+
+impl Reservations {
+    fn is_free(&self, cell: usize, time: usize) -> bool {
+        !self.cells.contains(&(cell, time)) && self.parked.get(&cell).map_or(true, |&from| time < from)
+    }
+
+    fn reserve(&mut self, path: &[usize]) {
+        for (time, &cell) in path.iter().enumerate() {
+            self.cells.insert((cell, time));
+            let last = self.last_visit.entry(cell).or_insert(time);
+            *last = (*last).max(time);
+        }
+        for (time, pair) in path.windows(2).enumerate() {
+            self.moves.insert((pair[0], pair[1], time));
+        }
+        if let Some(&goal) = path.last() {
+            self.parked.insert(goal, path.len() - 1);
+        }
+        self.until = self.until.max(path.len());
+    }
+}
+
+impl Fleet {
+    fn cost(agent: &FleetAgent) -> usize {
+        agent.path.len().saturating_sub(1)
+    }
+
+    /// Where an agent stands at the current timestep.
+    pub fn position(&self, agent: &FleetAgent) -> (usize, usize) {
+        match agent.path.get(self.time) {
+            Some(&cell) => cell,
+            None => agent.path.last().copied().unwrap_or(agent.start),
+        }
+    }
+
+    /// Timesteps until the last agent has arrived.
+    pub fn makespan(&self) -> Option<usize> {
+        self.solved.then(|| self.agents.iter().map(Fleet::cost).max().unwrap_or(0))
+    }
+
+    /// Timesteps each agent spends before arriving for good, added up.
+    pub fn sum_of_costs(&self) -> Option<usize> {
+        self.solved.then(|| self.agents.iter().map(Fleet::cost).sum())
+    }
+}
+
+impl Maze {
+    /// Picks `count` agents with distinct random starts and distinct random goals.
+    pub fn random_agent_pairs(&mut self, count: usize) -> Vec<((usize, usize), (usize, usize))> {
+        let cells: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .collect();
+        let count = count.min(cells.len() / 2);
+        let chosen: Vec<_> = cells.choose_multiple(&mut self.rng, 2 * count).copied().collect();
+        chosen.chunks(2).map(|pair| (pair[0], pair[1])).collect()
+    }
+
+    /// Prepares a fleet of agents to be planned and moved, replacing any solver.
+    pub fn init_fleet(&mut self, pairs: Vec<((usize, usize), (usize, usize))>) {
+        let agents = pairs
+            .into_iter()
+            .map(|(start, goal)| FleetAgent { start, goal, path: Vec::new() })
+            .collect();
+        self.fleet = Some(Fleet {
+            agents,
+            time: 0,
+            planned: false,
+            solved: false,
+            attempts: 0,
+        });
+        self.solver = None;
+        self.dynamic_walls = None; // Reservations only hold while the walls stay put
+    }
+
+    /// Plans the fleet on the first step, then moves every agent one cell per step.
+    /// Returns false once every agent has arrived or no plan could be found.
+    pub(super) fn fleet_step(&mut self, steps: usize, solve_time: &mut Duration) -> bool {
+        let start = Instant::now();
+        let Some(mut fleet) = self.fleet.take() else {
+            return false;
+        };
+
+        if !fleet.planned {
+            self.plan_fleet(&mut fleet);
+        }
+        let still_running = match fleet.makespan() {
+            Some(makespan) => {
+                fleet.time = (fleet.time + steps).min(makespan);
+                fleet.time < makespan
+            }
+            None => false,
+        };
+        self.fleet = Some(fleet);
+
+        *solve_time += start.elapsed();
+        still_running
+    }
+
+    fn plan_fleet(&mut self, fleet: &mut Fleet) {
+        fleet.planned = true;
+        let mut order: Vec<usize> = (0..fleet.agents.len()).collect();
+
+        for attempt in 0..ATTEMPTS {
+            fleet.attempts = attempt + 1;
+            match attempt {
+                0 => {}
+                1 => order.reverse(),
+                _ => order.shuffle(&mut self.rng),
+            }
+
+            let mut reservations = Reservations::default();
+            let mut paths = vec![Vec::new(); fleet.agents.len()];
+            let solved = order.iter().all(|&agent| {
+                let FleetAgent { start, goal, .. } = fleet.agents[agent];
+                let Some(path) = self.plan_agent(start, goal, &reservations) else {
+                    return false;
+                };
+                reservations.reserve(&path);
+                paths[agent] = path;
+                true
+            });
+
+            if solved {
+                for (agent, path) in fleet.agents.iter_mut().zip(paths) {
+                    agent.path = path.into_iter().map(|cell| (cell % self.width, cell / self.width)).collect();
+                }
+                fleet.solved = true;
+                return;
+            }
+        }
+    }
+
+    /// Space-time A* around the reservations, with waiting allowed. Returns the
+    /// cell index at every timestep until the agent can stay on its goal.
+    fn plan_agent(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        reservations: &Reservations,
+    ) -> Option<Vec<usize>> {
+        // True distances to the goal make the heuristic exact when nobody is in the way
+        let distance = self.distance_field(goal).distance;
+        let start = start.1 * self.width + start.0;
+        let goal = goal.1 * self.width + goal.0;
+        let free_from = reservations.last_visit.get(&goal).map_or(0, |&last| last + 1);
+        let horizon = reservations.until + distance[start]? + self.width + self.height;
+
+        let mut queue = BinaryHeap::from([Reverse((distance[start]?, Reverse(0), start))]);
+        let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut closed = HashSet::new();
+
+        while let Some(Reverse((_, Reverse(time), cell))) = queue.pop() {
+            if !closed.insert((cell, time)) {
+                continue;
+            }
+            if cell == goal && time >= free_from {
+                let mut path = vec![cell];
+                let mut state = (cell, time);
+                while let Some(&previous) = came_from.get(&state) {
+                    path.push(previous.0);
+                    state = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if time == horizon {
+                continue;
+            }
+
+            let (x, y) = (cell % self.width, cell / self.width);
+            let moves = self.open_neighbors(x, y).into_iter().map(|(nx, ny)| ny * self.width + nx);
+            for next in moves.chain([cell]) {
+                let arrival = time + 1;
+                if closed.contains(&(next, arrival))
+                    || !reservations.is_free(next, arrival)
+                    || reservations.moves.contains(&(next, cell, time))
+                {
+                    continue;
+                }
+                let Some(remaining) = distance[next] else {
+                    continue;
+                };
+                if let Entry::Vacant(entry) = came_from.entry((next, arrival)) {
+                    entry.insert((cell, time));
+                    queue.push(Reverse((arrival + remaining, Reverse(arrival), next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::app::maze::{generated, MazeEvent};
+    use crate::app::MazeAlgorithms;
+
+    use super::*;
+
+    fn run(maze: &mut Maze) -> Fleet {
+        let mut solve_time = Duration::ZERO;
+        while maze.fleet_step(1, &mut solve_time) {}
+        maze.fleet.clone().unwrap()
+    }
+
+    /// A corridor along the top row with a single side pocket below (1, 0).
+    fn corridor_with_pocket() -> Maze {
+        let mut maze = Maze::new(6, 2);
+        for x in 0..5 {
+            maze.record(MazeEvent::RemoveWall { x, y: 0, wall: 1 });
+        }
+        maze.record(MazeEvent::RemoveWall { x: 1, y: 0, wall: 2 });
+        maze
+    }
+
+    #[test]
+    fn test_fleet_never_collides() {
+        let mut maze = generated(15, 15, MazeAlgorithms::Prims, 0.3);
+
+        let pairs = maze.random_agent_pairs(8);
+        maze.init_fleet(pairs);
+        let fleet = run(&mut maze);
+        assert!(fleet.solved);
+
+        let makespan = fleet.makespan().unwrap();
+        let at = |agent: &FleetAgent, time: usize| agent.path[time.min(agent.path.len() - 1)];
+        for time in 0..=makespan {
+            let cells: HashSet<_> = fleet.agents.iter().map(|agent| at(agent, time)).collect();
+            assert_eq!(cells.len(), fleet.agents.len(), "two agents share a cell at {}", time);
+
+            for (i, a) in fleet.agents.iter().enumerate() {
+                let (from, to) = (at(a, time), at(a, time + 1));
+                assert!(from == to || maze.open_neighbors(from.0, from.1).contains(&to));
+                for b in &fleet.agents[i + 1..] {
+                    assert!(!(at(b, time) == to && at(b, time + 1) == from), "agents swap at {}", time);
+                }
+            }
+        }
+        for agent in &fleet.agents {
+            assert_eq!(agent.path.first(), Some(&agent.start));
+            assert_eq!(agent.path.last(), Some(&agent.goal));
+        }
+        assert_eq!(fleet.time, makespan);
+    }
+
+    #[test]
+    fn test_agent_steps_aside_into_pocket() {
+        let mut maze = corridor_with_pocket();
+        maze.init_fleet(vec![((0, 0), (5, 0)), ((5, 0), (0, 0))]);
+        let fleet = run(&mut maze);
+
+        // The first order deadlocks, in the second the agent from the right passes straight through
+        assert!(fleet.solved);
+        assert_eq!(fleet.attempts, 2);
+        assert!(fleet.agents[0].path.contains(&(1, 1)));
+        assert_eq!(fleet.makespan(), Some(9));
+        assert_eq!(fleet.sum_of_costs(), Some(9 + 5));
+    }
+
+    #[test]
+    fn test_corridor_swap_has_no_plan() {
+        let mut maze = Maze::new(5, 1);
+        for x in 0..4 {
+            maze.record(MazeEvent::RemoveWall { x, y: 0, wall: 1 });
+        }
+        maze.init_fleet(vec![((0, 0), (4, 0)), ((4, 0), (0, 0))]);
+        let fleet = run(&mut maze);
+
+        assert!(!fleet.solved);
+        assert_eq!(fleet.attempts, ATTEMPTS);
+        assert_eq!(fleet.makespan(), None);
+    }
+}
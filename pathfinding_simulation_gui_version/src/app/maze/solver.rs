@@ -0,0 +1,718 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::app::{Heuristic, PathfindingAlgorithms};
+
+use super::agents::Agent;
+use super::d_star_lite::Explorer;
+use super::dynamic_walls::Execution;
+use super::iterative_deepening::Deepening;
+use super::{Maze, Topology};
+
+/// Heap priorities are stored as fixed point so fractional heuristics keep their order.
+const PRIORITY_SCALE: f64 = 1024.0;
+
+/// Tuning for the informed searches.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolverOptions {
+    pub heuristic: Heuristic,
+    pub weight: f64,          // Epsilon for weighted A*, ignored by the other algorithms
+    pub sensor_radius: usize, // How far the D* Lite agent sees walls, in cells
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self {
+            heuristic: Heuristic::Manhattan,
+            weight: 1.0,
+            sensor_radius: 2,
+        }
+    }
+}
+
+/// Search state of a single cell, used to colour the maze while solving.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CellMark {
+    Unseen,
+    Open,   // Cell is on the frontier
+    Closed, // Cell has been expanded
+    Path,   // Cell is part of the found path
+    Scanned, // Cell was passed over by a jump, without being queued
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Frontier {
+    Stack(Vec<usize>),
+    Queue(VecDeque<usize>),
+    Heap(BinaryHeap<Reverse<(u64, usize)>>), // (fixed point priority, cell index)
+}
+
+/// One search tree growing from `origin` towards `target`. Bidirectional
+/// algorithms run two of them, one from each end.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Search {
+    pub origin: (usize, usize),
+    pub target: (usize, usize),
+    frontier: Frontier,
+    pub(super) came_from: Vec<Option<usize>>,
+    pub(super) cost: Vec<usize>,
+    pub marks: Vec<CellMark>,
+    pub expanded: usize, // Number of cells taken off the frontier
+    #[serde(default)]
+    pub peak_memory: usize, // Most cells held at once: frontier plus closed set, or the path when deepening
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MazeSolver {
+    pub algorithm: PathfindingAlgorithms,
+    pub options: SolverOptions,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    #[serde(default)]
+    pub topology: Topology, // Shape of the maze's cells, which the heuristics measure by
+    pub forward: Search,
+    pub backward: Option<Search>, // Search from the goal, only for bidirectional algorithms
+    forward_turn: bool,           // Which search a bidirectional solver expands next
+    meeting: Option<(usize, usize)>, // Best (cell, path cost) where the two searches touched
+    #[serde(default)]
+    pub deepening: Option<Deepening>, // Bound and current path, only for iterative deepening algorithms
+    #[serde(default)]
+    pub agent: Option<Agent>, // Walker or dead-end filler, only for the maze walking strategies
+    #[serde(default)]
+    pub explorer: Option<Explorer>, // Agent exploring the maze under fog of war, only for D* Lite
+    #[serde(default)]
+    pub execution: Option<Execution>, // Plan being walked while doors open and close
+    #[serde(default)]
+    pub replans: usize, // Times the plan had to be repaired or redone after walls changed
+    pub path: Option<Vec<(usize, usize)>>,
+    pub finished: bool,
+    pub optimal_cost: Option<usize>, // Cost found by a Dijkstra baseline once finished
+}
+
+impl Search {
+    fn new(maze: &Maze, algorithm: PathfindingAlgorithms, origin: (usize, usize), target: (usize, usize)) -> Self {
+        let cells = maze.width * maze.height;
+
+        let frontier = match algorithm {
+            // Iterative deepening and the walking strategies keep their own state instead of a frontier
+            PathfindingAlgorithms::Dfs
+            | PathfindingAlgorithms::IterativeDeepeningDfs
+            | PathfindingAlgorithms::IdaStar
+            | PathfindingAlgorithms::LeftWallFollower
+            | PathfindingAlgorithms::RightWallFollower
+            | PathfindingAlgorithms::Pledge
+            | PathfindingAlgorithms::Tremaux
+            | PathfindingAlgorithms::DeadEndFilling
+            | PathfindingAlgorithms::DStarLite => Frontier::Stack(Vec::new()),
+            PathfindingAlgorithms::Bfs | PathfindingAlgorithms::BidirectionalBfs => {
+                Frontier::Queue(VecDeque::new())
+            }
+            PathfindingAlgorithms::Astar
+            | PathfindingAlgorithms::Dijkstra
+            | PathfindingAlgorithms::GreedyBestFirst
+            | PathfindingAlgorithms::WeightedAstar
+            | PathfindingAlgorithms::BidirectionalAstar
+            | PathfindingAlgorithms::JumpPointSearch => Frontier::Heap(BinaryHeap::new()),
+        };
+
+        Search {
+            origin,
+            target,
+            frontier,
+            came_from: vec![None; cells],
+            cost: vec![usize::MAX; cells],
+            marks: vec![CellMark::Unseen; cells],
+            expanded: 0,
+            peak_memory: 0,
+        }
+    }
+
+    pub(super) fn push(&mut self, cell: usize, priority: f64) {
+        match &mut self.frontier {
+            Frontier::Stack(stack) => stack.push(cell),
+            Frontier::Queue(queue) => queue.push_back(cell),
+            Frontier::Heap(heap) => heap.push(Reverse(((priority * PRIORITY_SCALE) as u64, cell))),
+        }
+        self.marks[cell] = CellMark::Open;
+        self.peak_memory = self.peak_memory.max(self.frontier_len() + self.expanded);
+    }
+
+    /// Entries on the frontier, including stale ones that will be skipped.
+    fn frontier_len(&self) -> usize {
+        match &self.frontier {
+            Frontier::Stack(stack) => stack.len(),
+            Frontier::Queue(queue) => queue.len(),
+            Frontier::Heap(heap) => heap.len(),
+        }
+    }
+
+    /// Takes the next cell to expand off the frontier, skipping stale entries.
+    fn pop(&mut self) -> Option<usize> {
+        loop {
+            let cell = match &mut self.frontier {
+                Frontier::Stack(stack) => stack.pop(),
+                Frontier::Queue(queue) => queue.pop_front(),
+                Frontier::Heap(heap) => heap.pop().map(|Reverse((_, cell))| cell),
+            }?;
+            // Cells can be queued more than once
+            if self.marks[cell] != CellMark::Closed {
+                return Some(cell);
+            }
+        }
+    }
+
+    /// Lower bound on the priority of anything left on the frontier.
+    fn min_priority(&self) -> Option<f64> {
+        match &self.frontier {
+            Frontier::Stack(stack) => stack.last().map(|&cell| self.cost[cell] as f64),
+            Frontier::Queue(queue) => queue.front().map(|&cell| self.cost[cell] as f64),
+            Frontier::Heap(heap) => heap.peek().map(|Reverse((priority, _))| *priority as f64 / PRIORITY_SCALE),
+        }
+    }
+
+    /// Cells from `cell` back to this search's origin.
+    fn chain_to_origin(&self, cell: usize) -> Vec<usize> {
+        let mut chain = vec![cell];
+        let mut current = cell;
+        while let Some(previous) = self.came_from[current] {
+            chain.push(previous);
+            current = previous;
+        }
+        chain
+    }
+}
+
+impl MazeSolver {
+    /// Cost of the found path, counted in moves between cells.
+    pub fn path_cost(&self) -> Option<usize> {
+        self.path.as_ref().map(|path| path.len().saturating_sub(1))
+    }
+
+    /// Whether the found path is as short as the Dijkstra baseline's.
+    /// None while the search is running or when no path exists.
+    pub fn is_optimal(&self) -> Option<bool> {
+        Some(self.path_cost()? == self.optimal_cost?)
+    }
+
+    /// Cells expanded by both searches together.
+    pub fn expanded(&self) -> usize {
+        self.forward.expanded + self.backward.as_ref().map_or(0, |search| search.expanded)
+    }
+
+    /// Peak memory of both searches together, counted in cells.
+    pub fn peak_memory(&self) -> usize {
+        self.forward.peak_memory + self.backward.as_ref().map_or(0, |search| search.peak_memory)
+    }
+
+    pub(super) fn priority(&self, search: &Search, cost: usize, cell: (usize, usize)) -> f64 {
+        let heuristic = if self.algorithm.uses_heuristic() {
+            self.options.heuristic.distance(self.topology, cell, search.target)
+        } else {
+            0.0
+        };
+
+        match self.algorithm {
+            PathfindingAlgorithms::GreedyBestFirst => heuristic,
+            PathfindingAlgorithms::WeightedAstar => cost as f64 + self.options.weight * heuristic,
+            _ => cost as f64 + heuristic,
+        }
+    }
+
+    /// Whether the best meeting found so far can no longer be beaten.
+    fn meeting_is_final(&self, meeting_cost: usize) -> bool {
+        let Some(backward) = &self.backward else {
+            return true;
+        };
+        let (Some(forward_min), Some(backward_min)) =
+            (self.forward.min_priority(), backward.min_priority())
+        else {
+            return true; // One side has run dry, nothing left can improve the path
+        };
+
+        let meeting_cost = meeting_cost as f64;
+        if self.algorithm.uses_heuristic() {
+            // With a consistent heuristic each side's smallest f bounds every unexplored path
+            forward_min.max(backward_min) >= meeting_cost
+        } else {
+            forward_min + backward_min >= meeting_cost
+        }
+    }
+}
+
+impl Maze {
+    /// Prepares a step-wise search from `start` to `goal` with the chosen algorithm.
+    pub fn init_solver(
+        &mut self,
+        algorithm: PathfindingAlgorithms,
+        options: SolverOptions,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) {
+        self.solver = Some(self.new_solver(algorithm, options, start, goal));
+        self.fleet = None;
+    }
+
+    pub(super) fn new_solver(
+        &self,
+        algorithm: PathfindingAlgorithms,
+        options: SolverOptions,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> MazeSolver {
+        let backward = algorithm
+            .is_bidirectional()
+            .then(|| Search::new(self, algorithm, goal, start));
+
+        let mut solver = MazeSolver {
+            algorithm,
+            options,
+            start,
+            goal,
+            topology: self.grid.topology(),
+            forward: Search::new(self, algorithm, start, goal),
+            backward,
+            forward_turn: true,
+            meeting: None,
+            deepening: None,
+            agent: None,
+            explorer: None,
+            execution: None,
+            replans: 0,
+            path: None,
+            finished: false,
+            optimal_cost: None,
+        };
+
+        let start_index = start.1 * self.width + start.0;
+        let priority = solver.priority(&solver.forward, 0, start);
+
+        if algorithm.is_agent() {
+            self.new_agent(&mut solver);
+            return solver;
+        }
+        if algorithm == PathfindingAlgorithms::DStarLite {
+            self.new_explorer(&mut solver);
+            return solver;
+        }
+        if algorithm.is_iterative_deepening() {
+            solver.deepening = Some(Deepening::new(priority));
+            return solver;
+        }
+
+        solver.forward.cost[start_index] = 0;
+        solver.forward.push(start_index, priority);
+
+        if let Some(mut backward) = solver.backward.take() {
+            let goal_index = goal.1 * self.width + goal.0;
+            let priority = solver.priority(&backward, 0, goal);
+            backward.cost[goal_index] = 0;
+            backward.push(goal_index, priority);
+            solver.backward = Some(backward);
+        }
+
+        solver
+    }
+
+    /// Length of the shortest path between two cells, found by running Dijkstra to completion.
+    pub fn shortest_path_cost(&self, start: (usize, usize), goal: (usize, usize)) -> Option<usize> {
+        let mut baseline =
+            self.new_solver(PathfindingAlgorithms::Dijkstra, SolverOptions::default(), start, goal);
+        while self.expand_next(&mut baseline) {}
+        baseline.path_cost()
+    }
+
+    /// Advances the current search by up to `steps` expansions.
+    /// Returns false once the search has finished (with or without a path).
+    pub fn solve_step(&mut self, steps: usize, solve_time: &mut Duration) -> bool {
+        if self.fleet.is_some() {
+            return self.fleet_step(steps, solve_time);
+        }
+        let start = Instant::now();
+
+        let mut solver = match self.solver.take() {
+            Some(solver) => solver,
+            None => return false,
+        };
+
+        let was_finished = solver.finished;
+        for _ in 0..steps {
+            if solver.finished {
+                break;
+            }
+
+            // Doors open and close between steps, the solver finds out as it goes
+            self.advance_dynamic_walls();
+            if self.is_executing(&solver) {
+                if !self.execute_step(&mut solver) {
+                    solver.finished = true;
+                    break;
+                }
+                continue;
+            }
+
+            if !self.expand_next(&mut solver) {
+                if self.executes_plan(&solver) {
+                    if solver.path.is_some() {
+                        self.follow_plan(&mut solver);
+                        continue;
+                    }
+                    // A replan can come up empty while a door is closed, so wait for it to open
+                    if solver.execution.is_some() {
+                        self.wait_for_door(&mut solver);
+                        continue;
+                    }
+                }
+                solver.finished = true;
+                break;
+            }
+        }
+        *solve_time += start.elapsed();
+
+        // The baseline is only for judging the result, so it stays off the clock
+        if solver.finished && !was_finished {
+            solver.optimal_cost = self.shortest_path_cost(solver.start, solver.goal);
+        }
+
+        let still_running = !solver.finished;
+        self.solver = Some(solver);
+        still_running
+    }
+
+    /// Expands a single cell. Returns false once a path has been found
+    /// or the frontier is exhausted.
+    fn expand_next(&self, solver: &mut MazeSolver) -> bool {
+        if solver.backward.is_some() {
+            return self.expand_bidirectional(solver);
+        }
+        if solver.deepening.is_some() {
+            return self.expand_deepening(solver);
+        }
+        if solver.agent.is_some() {
+            return self.step_agent(solver);
+        }
+        if solver.explorer.is_some() {
+            return self.step_explorer(solver);
+        }
+
+        let Some(current) = solver.forward.pop() else {
+            return false; // Frontier exhausted, goal unreachable
+        };
+
+        // The pruning rules are for square cells, on hex cells the search runs as plain A*
+        if solver.algorithm == PathfindingAlgorithms::JumpPointSearch && solver.topology == Topology::Square {
+            if self.expand_jump_point(solver, current) == Some(current) {
+                let jump_points = solver.forward.chain_to_origin(current).into_iter().rev().collect();
+                let path = self.fill_jumps(jump_points);
+                self.set_path(solver, path);
+                return false;
+            }
+            return true;
+        }
+
+        if self.expand(solver, current, true) == Some(current) {
+            let path = solver.forward.chain_to_origin(current).into_iter().rev().collect();
+            self.set_path(solver, path);
+            return false;
+        }
+        true
+    }
+
+    /// Expands one cell of whichever search's turn it is, keeping track of
+    /// where the two searches meet.
+    fn expand_bidirectional(&self, solver: &mut MazeSolver) -> bool {
+        if let Some((meeting, cost)) = solver.meeting {
+            if solver.meeting_is_final(cost) {
+                self.join_searches(solver, meeting);
+                return false;
+            }
+        }
+
+        let forward = solver.forward_turn;
+        solver.forward_turn = !solver.forward_turn;
+
+        let search = if forward { &mut solver.forward } else { solver.backward.as_mut().unwrap() };
+        let Some(current) = search.pop() else {
+            // One search ran dry: either the best meeting stands or there is no path
+            if let Some((meeting, _)) = solver.meeting {
+                self.join_searches(solver, meeting);
+            }
+            return false;
+        };
+
+        self.expand(solver, current, forward);
+        true
+    }
+
+    /// Closes `current` in one of the searches and relaxes its neighbours.
+    /// Returns the cell if it is the search's target.
+    fn expand(&self, solver: &mut MazeSolver, current: usize, forward: bool) -> Option<usize> {
+        let (x, y) = (current % self.width, current / self.width);
+
+        let (search, other) = if forward {
+            (&mut solver.forward, solver.backward.as_ref())
+        } else {
+            (solver.backward.as_mut().unwrap(), Some(&solver.forward))
+        };
+        search.marks[current] = CellMark::Closed;
+        search.expanded += 1;
+
+        if other.is_none() && (x, y) == search.target {
+            return Some(current);
+        }
+
+        let mut touched = Vec::new();
+        let mut pushes = Vec::new();
+
+        if let Some(other) = other {
+            if other.cost[current] != usize::MAX {
+                touched.push((current, search.cost[current] + other.cost[current]));
+            }
+        }
+
+        for (nx, ny) in self.open_neighbors(x, y) {
+            let neighbor = ny * self.width + nx;
+            if search.marks[neighbor] == CellMark::Closed {
+                continue;
+            }
+
+            let new_cost = search.cost[current] + 1;
+            let improves = match solver.algorithm {
+                // DFS re-parents on every push so the most recent branch wins
+                PathfindingAlgorithms::Dfs => true,
+                _ => new_cost < search.cost[neighbor],
+            };
+
+            if improves {
+                search.cost[neighbor] = new_cost;
+                search.came_from[neighbor] = Some(current);
+                pushes.push((neighbor, new_cost, (nx, ny)));
+            }
+
+            // Neighbours the other search has already reached are possible meeting points
+            if let Some(other) = other {
+                if other.cost[neighbor] != usize::MAX {
+                    touched.push((neighbor, search.cost[neighbor] + other.cost[neighbor]));
+                }
+            }
+        }
+
+        for (cell, cost, position) in pushes {
+            let search = if forward { &solver.forward } else { solver.backward.as_ref().unwrap() };
+            let priority = solver.priority(search, cost, position);
+            let search = if forward { &mut solver.forward } else { solver.backward.as_mut().unwrap() };
+            search.push(cell, priority);
+        }
+
+        for (cell, cost) in touched {
+            if solver.meeting.map_or(true, |(_, best)| cost < best) {
+                solver.meeting = Some((cell, cost));
+            }
+        }
+        None
+    }
+
+    /// Builds the final path through the cell where the two searches meet.
+    fn join_searches(&self, solver: &mut MazeSolver, meeting: usize) {
+        let mut path = solver.forward.chain_to_origin(meeting);
+        path.reverse();
+        if let Some(backward) = &solver.backward {
+            path.extend(backward.chain_to_origin(meeting).into_iter().skip(1));
+        }
+        self.set_path(solver, path);
+    }
+
+    pub(super) fn set_path(&self, solver: &mut MazeSolver, path: Vec<usize>) {
+        for &cell in &path {
+            solver.forward.marks[cell] = CellMark::Path;
+        }
+        solver.path = Some(
+            path.into_iter()
+                .map(|cell| (cell % self.width, cell / self.width))
+                .collect(),
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::maze::{generated, solve, solve_with};
+    use crate::app::MazeAlgorithms;
+
+    #[test]
+    fn test_every_algorithm_finds_a_path() {
+        let maze = generated(20, 15, MazeAlgorithms::Dfs, 0.0);
+
+        for algorithm in PathfindingAlgorithms::ALL {
+            let mut maze = maze.clone();
+            assert!(solve(&mut maze, algorithm).is_some(), "{} found no path", algorithm);
+
+            let path = maze.solver.as_ref().unwrap().path.clone().unwrap();
+            assert_eq!(path.first(), Some(&(0, 0)));
+            assert_eq!(path.last(), Some(&(19, 14)));
+            for pair in path.windows(2) {
+                let (x, y) = pair[0];
+                assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimal_algorithms_agree_on_cost() {
+        let mut maze = generated(25, 25, MazeAlgorithms::Dfs, 0.0);
+
+        // Open up some extra walls so there is more than one route
+        for y in 0..maze.height {
+            for x in (0..maze.width - 1).step_by(3) {
+                maze.grid.set_wall(x, y, 1, false);
+            }
+        }
+
+        let bfs = solve(&mut maze.clone(), PathfindingAlgorithms::Bfs);
+        assert_eq!(solve(&mut maze.clone(), PathfindingAlgorithms::Dijkstra), bfs);
+        assert_eq!(solve(&mut maze.clone(), PathfindingAlgorithms::Astar), bfs);
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let mut maze = Maze::new(5, 5); // Every wall is still standing
+        assert_eq!(solve(&mut maze, PathfindingAlgorithms::Astar), None);
+        assert!(maze.solver.as_ref().unwrap().finished);
+    }
+
+    #[test]
+    fn test_every_heuristic_keeps_astar_optimal() {
+        let mut maze = generated(20, 20, MazeAlgorithms::Dfs, 0.0);
+        for y in (0..maze.height - 1).step_by(2) {
+            for x in 0..maze.width {
+                maze.grid.set_wall(x, y, 2, false);
+            }
+        }
+
+        let optimal = maze.shortest_path_cost((0, 0), (19, 19));
+        for heuristic in Heuristic::ALL {
+            let options = SolverOptions { heuristic, ..SolverOptions::default() };
+            let mut maze = maze.clone();
+            let cost = solve_with(&mut maze, PathfindingAlgorithms::Astar, options, (0, 0), (19, 19));
+            assert_eq!(cost, optimal, "{}", heuristic);
+            assert_eq!(maze.solver.as_ref().unwrap().is_optimal(), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_solvers_work_on_hex_mazes() {
+        let mut maze = Maze::with_topology(16, 12, Topology::Hex);
+        maze.seed(5);
+        maze.init_prims();
+        let mut generation_time = Duration::ZERO;
+        while maze.step(1000, &mut generation_time) {}
+
+        // A perfect maze has one route, and every strategy ends up on it. Only
+        // D* Lite reports the walk it took, dead ends it backed out of included.
+        let optimal = maze.shortest_path_cost((0, 0), (15, 11));
+        for algorithm in PathfindingAlgorithms::ALL {
+            let mut maze = maze.clone();
+            let cost = solve(&mut maze, algorithm);
+            if algorithm == PathfindingAlgorithms::DStarLite {
+                assert!(cost >= optimal);
+            } else {
+                assert_eq!(cost, optimal, "{}", algorithm);
+            }
+            let path = maze.solver.as_ref().unwrap().path.clone().unwrap();
+            for pair in path.windows(2) {
+                let (x, y) = pair[0];
+                assert!(maze.open_neighbors(x, y).contains(&pair[1]));
+            }
+        }
+
+        // Manhattan distance overestimates on hex cells, the heuristics have to count hex steps
+        maze.braid(0.5);
+        let optimal = maze.shortest_path_cost((0, 0), (15, 11));
+        for heuristic in Heuristic::ALL {
+            let options = SolverOptions { heuristic, ..SolverOptions::default() };
+            for algorithm in [PathfindingAlgorithms::Astar, PathfindingAlgorithms::BidirectionalAstar] {
+                let mut maze = maze.clone();
+                let cost = solve_with(&mut maze, algorithm, options, (0, 0), (15, 11));
+                assert_eq!(cost, optimal, "{} with {}", algorithm, heuristic);
+            }
+        }
+    }
+
+    #[test]
+    fn test_greedy_expands_less_on_open_grid() {
+        let mut maze = Maze::new(30, 30);
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                maze.grid.set_walls(x, y, &[y == 0, x == 29, y == 29, x == 0]);
+            }
+        }
+
+        let options = SolverOptions { weight: 3.0, ..SolverOptions::default() };
+        let mut greedy = maze.clone();
+        let mut weighted = maze.clone();
+        let mut dijkstra = maze.clone();
+        let corner = (29, 29);
+        assert_eq!(solve_with(&mut greedy, PathfindingAlgorithms::GreedyBestFirst, options, (0, 0), corner), Some(58));
+        assert!(solve_with(&mut weighted, PathfindingAlgorithms::WeightedAstar, options, (0, 0), corner).is_some());
+        solve(&mut dijkstra, PathfindingAlgorithms::Dijkstra);
+
+        let expanded = |maze: &Maze| maze.solver.as_ref().unwrap().expanded();
+        assert!(expanded(&greedy) < expanded(&dijkstra));
+        assert!(expanded(&weighted) < expanded(&dijkstra));
+    }
+
+    #[test]
+    fn test_bidirectional_searches_are_optimal() {
+        let mut maze = generated(30, 30, MazeAlgorithms::Dfs, 0.0);
+        for y in (1..maze.height - 1).step_by(4) {
+            for x in 0..maze.width {
+                maze.grid.set_wall(x, y, 2, false);
+            }
+        }
+        let optimal = maze.shortest_path_cost((0, 0), (29, 29));
+
+        for algorithm in [PathfindingAlgorithms::BidirectionalBfs, PathfindingAlgorithms::BidirectionalAstar] {
+            let mut maze = maze.clone();
+            assert_eq!(solve(&mut maze, algorithm), optimal, "{}", algorithm);
+
+            let solver = maze.solver.as_ref().unwrap();
+            let path = solver.path.as_ref().unwrap();
+            assert_eq!(path.first(), Some(&(0, 0)));
+            assert_eq!(path.last(), Some(&(29, 29)));
+            assert!(solver.forward.expanded > 0 && solver.backward.as_ref().unwrap().expanded > 0);
+        }
+    }
+
+    #[test]
+    fn test_bidirectional_bfs_explores_less_on_open_grid() {
+        let mut maze = Maze::new(40, 40);
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                maze.grid.set_walls(x, y, &[y == 0, x == 39, y == 39, x == 0]);
+            }
+        }
+
+        let mut bfs = maze.clone();
+        let mut bidirectional = maze.clone();
+        assert_eq!(solve(&mut bfs, PathfindingAlgorithms::Bfs), Some(78));
+        assert_eq!(solve(&mut bidirectional, PathfindingAlgorithms::BidirectionalBfs), Some(78));
+
+        let expanded = |maze: &Maze| maze.solver.as_ref().unwrap().expanded();
+        assert!(expanded(&bidirectional) < expanded(&bfs));
+    }
+
+    #[test]
+    fn test_bidirectional_same_start_and_goal() {
+        let mut maze = generated(5, 5, MazeAlgorithms::Dfs, 0.0);
+        maze.init_solver(PathfindingAlgorithms::BidirectionalBfs, SolverOptions::default(), (2, 2), (2, 2));
+        let mut solve_time = Duration::ZERO;
+        while maze.solve_step(10, &mut solve_time) {}
+        assert_eq!(maze.solver.as_ref().unwrap().path_cost(), Some(0));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::app::MazeAlgorithms;
+
+use super::{DisjointSet, Maze};
+
+// Statistical checks on the generators. A perfect maze is a spanning tree of
+// the grid, and on a tiny grid every spanning tree can be listed. Generating
+// thousands of seeded mazes and comparing how often each tree comes up with
+// a chi-square test shows whether a generator samples them uniformly.
+// Aldous-Broder should. The others are biased: the depth first search toward
+// long corridors, Prim's and Kruskal's toward short branches. The seeds are
+// fixed so the tests give the same answer every run.
+
+/// Every passage between two neighbouring cells, as (x, y, wall) with the wall
+/// pointing right or down, in a fixed order so a tree can be stored as bits.
+fn edges(width: usize, height: usize) -> Vec<(usize, usize, usize)> {
+    let mut edges = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if x + 1 < width {
+                edges.push((x, y, 1));
+            }
+            if y + 1 < height {
+                edges.push((x, y, 2));
+            }
+        }
+    }
+    edges
+}
+
+/// Every spanning tree of the grid, as bit sets over `edges`. Only meant for
+/// grids with a few dozen edges at most.
+fn spanning_trees(width: usize, height: usize) -> Vec<u64> {
+    let edges = edges(width, height);
+    let cells = width * height;
+    assert!(edges.len() < 64);
+
+    (0u64..1 << edges.len())
+        .filter(|subset| subset.count_ones() as usize == cells - 1)
+        .filter(|&subset| {
+            // cells - 1 edges without a cycle are a spanning tree
+            let mut sets = DisjointSet::new(cells);
+            edges.iter().enumerate().filter(|(i, _)| subset & 1 << i != 0).all(|(_, &(x, y, wall))| {
+                let (nx, ny) = if wall == 1 { (x + 1, y) } else { (x, y + 1) };
+                sets.union(y * width + x, ny * width + nx)
+            })
+        })
+        .collect()
+}
+
+/// The open passages of a maze as a bit set over `edges`.
+fn tree_of(maze: &Maze) -> u64 {
+    edges(maze.width, maze.height)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, (x, y, wall))| !maze.grid.wall(x, y, wall))
+        .fold(0, |tree, (i, _)| tree | 1 << i)
+}
+
+/// How often each tree was generated over `samples` seeded runs.
+fn sample(algorithm: MazeAlgorithms, width: usize, height: usize, samples: u64) -> HashMap<u64, usize> {
+    let mut counts = HashMap::new();
+    for seed in 0..samples {
+        let mut maze = Maze::new(width, height);
+        maze.seed(seed);
+        maze.init_generator(algorithm);
+        let mut generation_time = Duration::ZERO;
+        while maze.step(1000, &mut generation_time) {}
+        *counts.entry(tree_of(&maze)).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Pearson's statistic for the counts against an even spread over `trees`.
+fn chi_square(counts: &HashMap<u64, usize>, trees: &[u64], samples: u64) -> f64 {
+    let expected = samples as f64 / trees.len() as f64;
+    trees
+        .iter()
+        .map(|tree| {
+            let observed = counts.get(tree).copied().unwrap_or(0) as f64;
+            (observed - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Value the statistic stays below with probability 0.999 for a uniform
+/// generator, from the Wilson-Hilferty approximation of the chi-square
+/// distribution. 3.09 is the matching point of the standard normal.
+fn critical_value(degrees_of_freedom: usize) -> f64 {
+    let k = degrees_of_freedom as f64;
+    let spread = 2.0 / (9.0 * k);
+    k * (1.0 - spread + 3.09 * spread.sqrt()).powi(3)
+}
+
+/// Generates the mazes and returns the statistic together with its critical value.
+fn uniformity(algorithm: MazeAlgorithms, width: usize, height: usize, samples: u64) -> (f64, f64) {
+    let trees = spanning_trees(width, height);
+    let counts = sample(algorithm, width, height, samples);
+    // Every maze has to be one of the listed trees, or the test means nothing
+    assert_eq!(counts.values().sum::<usize>() as u64, samples);
+    assert!(counts.keys().all(|tree| trees.contains(tree)), "{:?} made a maze that is not a spanning tree", algorithm);
+    (chi_square(&counts, &trees, samples), critical_value(trees.len() - 1))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spanning_tree_counts() {
+        // Known counts for grid graphs
+        assert_eq!(spanning_trees(2, 2).len(), 4);
+        assert_eq!(spanning_trees(3, 2).len(), 15);
+        assert_eq!(spanning_trees(3, 3).len(), 192);
+    }
+
+    #[test]
+    fn test_aldous_broder_is_uniform() {
+        let (statistic, critical) = uniformity(MazeAlgorithms::AldousBroder, 3, 3, 192 * 50);
+        assert!(statistic < critical, "chi-square {:.1} above {:.1}", statistic, critical);
+    }
+
+    #[test]
+    fn test_other_generators_are_biased() {
+        for algorithm in [MazeAlgorithms::Dfs, MazeAlgorithms::Prims, MazeAlgorithms::Kruskals] {
+            let (statistic, critical) = uniformity(algorithm, 3, 3, 192 * 50);
+            assert!(statistic > critical, "{:?}: chi-square {:.1} below {:.1}", algorithm, statistic, critical);
+        }
+    }
+
+    #[test]
+    fn test_every_generator_reaches_every_tree() {
+        // Except the depth first search, which only backs up once every neighbour is visited.
+        // It can't make the two rows joined in the middle: some row end would be a dead end
+        // next to an unvisited cell
+        let trees = spanning_trees(3, 2);
+        for algorithm in [MazeAlgorithms::Prims, MazeAlgorithms::Kruskals, MazeAlgorithms::AldousBroder] {
+            let counts = sample(algorithm, 3, 2, 15 * 40);
+            assert_eq!(counts.len(), trees.len(), "{:?} missed some trees", algorithm);
+        }
+    }
+}
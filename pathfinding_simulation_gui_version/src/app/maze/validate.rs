@@ -0,0 +1,148 @@
+use std::fmt;
+
+use super::{DisjointSet, Maze};
+
+// Structural checks for a maze. A perfect maze has a closed outer boundary and
+// exactly one path between any two cells: its passages form a spanning tree,
+// so it is connected and has no cycles. Walls between two cells are stored
+// once in the grid, so the two sides of a wall always agree.
+
+#[derive(Clone, Debug, Default)]
+pub struct Validation {
+    pub open_boundary: Vec<(usize, usize, usize)>, // (x, y, wall) open onto the outside of the grid
+    pub regions: usize,                            // Groups of cells that can reach each other
+    pub cycles: usize,                             // Independent loops: passages - cells + regions
+}
+
+impl Validation {
+    /// The outside is sealed.
+    pub fn is_consistent(&self) -> bool {
+        self.open_boundary.is_empty()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.regions == 1
+    }
+
+    pub fn is_perfect(&self) -> bool {
+        self.is_consistent() && self.is_connected() && self.cycles == 0
+    }
+}
+
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_perfect() {
+            return write!(f, "perfect");
+        }
+        let mut problems = Vec::new();
+        if !self.open_boundary.is_empty() {
+            problems.push(format!("{} open boundary walls", self.open_boundary.len()));
+        }
+        if !self.is_connected() {
+            problems.push(format!("{} regions", self.regions));
+        }
+        if self.cycles > 0 {
+            problems.push(format!("{} loops", self.cycles));
+        }
+        write!(f, "{}", problems.join(", "))
+    }
+}
+
+impl Maze {
+    /// Checks the walls and the passages they leave.
+    pub fn validate(&self) -> Validation {
+        let mut validation = Validation::default();
+        let cells = self.width * self.height;
+        let mut sets = DisjointSet::new(cells);
+        let mut passages = 0;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for wall in 0..self.grid.wall_count() {
+                    if self.grid.wall(x, y, wall) {
+                        continue;
+                    }
+                    let Some((nx, ny)) = self.grid.neighbor(x, y, wall) else {
+                        validation.open_boundary.push((x, y, wall));
+                        continue;
+                    };
+                    // Each passage is counted once, from the cell that comes first
+                    if ny * self.width + nx > y * self.width + x {
+                        passages += 1;
+                        sets.union(y * self.width + x, ny * self.width + nx);
+                    }
+                }
+            }
+        }
+
+        validation.regions = sets.count();
+        validation.cycles = (passages + validation.regions).saturating_sub(cells);
+        validation
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::app::maze::{generated, Topology};
+    use crate::app::MazeAlgorithms;
+
+    #[test]
+    fn test_generators_make_perfect_mazes() {
+        for algorithm in MazeAlgorithms::ALL {
+            let maze = generated(17, 11, algorithm, 0.0);
+
+            let validation = maze.validate();
+            assert!(validation.is_perfect(), "{}", validation);
+        }
+    }
+
+    #[test]
+    fn test_generators_make_perfect_hex_mazes() {
+        let generators: [fn(&mut Maze); 4] =
+            [Maze::init_dfs, Maze::init_prims, Maze::init_aldous_broder, Maze::init_kruskals];
+        for init in generators {
+            let mut maze = Maze::with_topology(13, 9, Topology::Hex);
+            init(&mut maze);
+            let mut generation_time = Duration::ZERO;
+            while maze.step(1000, &mut generation_time) {}
+
+            let validation = maze.validate();
+            assert!(validation.is_perfect(), "{}", validation);
+        }
+
+        // With every wall down there is a loop around each corner where three cells meet
+        let mut maze = Maze::with_topology(4, 3, Topology::Hex);
+        maze.braid(1.0);
+        let validation = maze.validate();
+        assert!(validation.is_consistent() && validation.is_connected());
+        assert_eq!(validation.cycles, 12);
+    }
+
+    #[test]
+    fn test_braided_maze_has_loops() {
+        let mut maze = Maze::new(10, 10);
+        maze.braid(1.0);
+
+        let validation = maze.validate();
+        assert!(validation.is_consistent() && validation.is_connected());
+        // A full grid has one loop per interior corner
+        assert_eq!(validation.cycles, 9 * 9);
+        assert!(!validation.is_perfect());
+    }
+
+    #[test]
+    fn test_broken_walls_are_reported() {
+        let mut maze = Maze::new(3, 3);
+        maze.grid.set_wall(1, 1, 1, false);
+        maze.grid.set_wall(2, 0, 0, false);
+
+        let validation = maze.validate();
+        assert_eq!(validation.open_boundary, vec![(2, 0, 0)]);
+        assert_eq!(validation.regions, 8);
+        assert_eq!(validation.to_string(), "1 open boundary walls, 8 regions");
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::app::{PathfindingAlgorithms, WindowState};
+use crate::Main;
+
+/// A race runs every pathfinder on its own copy of the same maze,
+/// with the same start and goal, stepping them in lockstep.
+pub struct Race {
+    pub window_ids: Vec<usize>,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+}
+
+pub struct LeaderboardEntry {
+    pub algorithm: PathfindingAlgorithms,
+    pub expanded: usize,
+    pub expanded_split: Option<(usize, usize)>, // (forward, backward) for bidirectional searches
+    pub peak_memory: usize,
+    pub path_cost: Option<usize>,
+    pub optimal: Option<bool>,
+    pub time: Duration,
+    pub finished: bool,
+    pub looping: bool, // A wall follower that gave up walking in circles
+}
+
+impl Race {
+    /// Current standings: finished searches first, ranked by how few cells they expanded.
+    pub fn leaderboard(&self, windows: &[WindowState]) -> Vec<LeaderboardEntry> {
+        let mut entries: Vec<LeaderboardEntry> = windows
+            .iter()
+            .filter(|window| self.window_ids.contains(&window.id))
+            .filter_map(|window| {
+                let solver = window.maze.solver.as_ref()?;
+                Some(LeaderboardEntry {
+                    algorithm: solver.algorithm,
+                    expanded: solver.expanded(),
+                    expanded_split: solver
+                        .backward
+                        .as_ref()
+                        .map(|backward| (solver.forward.expanded, backward.expanded)),
+                    peak_memory: solver.peak_memory(),
+                    path_cost: solver.path_cost(),
+                    optimal: solver.is_optimal(),
+                    time: window.solve_time,
+                    finished: solver.finished,
+                    looping: solver.is_looping(),
+                })
+            })
+            .collect();
+
+        entries.sort_by_key(|entry| (!entry.finished, entry.expanded, entry.time));
+        entries
+    }
+}
+
+impl Main {
+    /// Replaces the open windows with one window per pathfinder, each holding a
+    /// copy of the selected (fully generated) maze. Returns false if there is
+    /// no such maze to race on.
+    pub fn start_race(&mut self) -> bool {
+        let Some(source) = self
+            .windows
+            .iter()
+            .find(|window| Some(window.id) == self.selected_window_id)
+        else {
+            return false;
+        };
+
+        if !source.maze.is_generated() {
+            return false; // Racing on a half generated maze makes no sense
+        }
+
+        let mut maze = source.maze.clone();
+        maze.solver = None;
+        maze.fleet = None;
+        maze.disable_dynamic_walls(); // Every racer has to face the same walls
+
+        let (start, goal) = maze.suggested_endpoints();
+        let options = self.settings.solver_options();
+
+        self.windows.clear();
+        let mut window_ids = Vec::new();
+
+        for algorithm in PathfindingAlgorithms::ALL {
+            let mut window = WindowState::new(
+                self.next_window_id,
+                format!("Race {} | {}", self.next_window_id, algorithm),
+                maze.clone(),
+            );
+            window.maze.init_solver(algorithm, options, start, goal);
+            window.solving = true;
+
+            window_ids.push(window.id);
+            self.windows.push(window);
+            self.next_window_id += 1;
+        }
+
+        self.race = Some(Race {
+            window_ids,
+            start,
+            goal,
+        });
+        self.selected_window_id = None;
+        true
+    }
+}
@@ -1,21 +1,60 @@
-use crate::app::algorithms::{MazeAlgorithms, PathfindingAlgorithms};
+use serde::{Deserialize, Serialize};
 
+use crate::app::algorithms::{Heuristic, MazeAlgorithms, PathfindingAlgorithms};
+use crate::app::maze::{DoorMode, SolverOptions, Topology};
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)] // Fields missing from an older save fall back to their defaults
 pub struct AppSettings {
     pub maze_algorithm: MazeAlgorithms,
+    pub topology: Topology, // Shape of the cells of newly created mazes
     pub pathfinding_algorithm: PathfindingAlgorithms,
+    pub heuristic: Heuristic,
+    pub astar_weight: f64, // Epsilon the heuristic is inflated by in weighted A*
+    pub sensor_radius: usize, // How far the D* Lite agent can see under fog of war
+    pub dynamic_walls: bool,  // Whether doors open and close while the mazes are solved
+    pub door_mode: DoorMode,
+    pub door_count: usize,
+    pub door_period: usize,   // Solver steps between a door's toggles
+    pub agent_count: usize,   // Agents sharing each maze when solving them together
     pub maze_size: (usize, usize),
+    pub braid_fraction: f32, // Share of interior walls removed when opening up a maze
+    pub flood_source: (usize, usize), // Cell the distance map is measured from
     pub visualization_speed: i32,
     pub desired_fps: usize, 
+    pub restore_session: bool, // Whether open windows and their mazes survive a restart
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             maze_algorithm: MazeAlgorithms::Prims,
+            topology: Topology::Square,
             pathfinding_algorithm: PathfindingAlgorithms::Astar,
+            heuristic: Heuristic::Manhattan,
+            astar_weight: 2.0,
+            sensor_radius: 2,
+            dynamic_walls: false,
+            door_mode: DoorMode::Scheduled,
+            door_count: 30,
+            door_period: 50,
+            agent_count: 6,
             maze_size: (160, 100),
+            braid_fraction: 0.2,
+            flood_source: (0, 0),
             visualization_speed: 100,
             desired_fps: 60,
+            restore_session: true,
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn solver_options(&self) -> SolverOptions {
+        SolverOptions {
+            heuristic: self.heuristic,
+            weight: self.astar_weight,
+            sensor_radius: self.sensor_radius,
         }
     }
 }
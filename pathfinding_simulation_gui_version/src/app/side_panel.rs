@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use egui::InnerResponse;
 
-use crate::app::{MazeAlgorithms, WindowState};
+use crate::app::maze::{DoorMode, Topology};
+use crate::app::{FrameScheduler, MazeAlgorithms, WindowState};
 use crate::Main;
 
-use super::{Maze, PathfindingAlgorithms};
+use super::{Heuristic, Maze, PathfindingAlgorithms};
 
 impl Main {
     pub fn generate_side_panel(&mut self, ctx: &egui::Context, integration_info : &eframe::IntegrationInfo) -> InnerResponse<()> {
@@ -64,6 +67,18 @@ impl Main {
                 );
             });
 
+            ui.add_space(5.0);
+
+            ui.heading("cells");
+
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                for topology in Topology::ALL {
+                    ui.selectable_value(&mut self.settings.topology, topology, topology.to_string());
+                }
+            });
+
             ui.separator();
 
             ui.add_space(10.0);
@@ -71,17 +86,13 @@ impl Main {
             let create_window_btn = ui.button("Create window");
 
             if create_window_btn.clicked() && self.windows.len() < 4 {
-                let mut maze = Maze::new(self.settings.maze_size.0, self.settings.maze_size.1);
-
-                match self.settings.maze_algorithm {
-                    MazeAlgorithms::Dfs => maze.init_dfs(),
-                    MazeAlgorithms::Prims => maze.init_prims(),
-                    MazeAlgorithms::Kruskals => maze.init_kruskals(),
-                    MazeAlgorithms::AldousBroder => maze.init_aldous_broder(),
-                    // Add other algorithms as needed
-                }
-
+                let mut maze = Maze::with_topology(
+                    self.settings.maze_size.0,
+                    self.settings.maze_size.1,
+                    self.settings.topology,
+                );
 
+                maze.init_generator(self.settings.maze_algorithm);
 
                 let algorithm_name = self.settings.maze_algorithm.to_string();
 
@@ -101,10 +112,24 @@ impl Main {
 
             if start_maze_generation_bth.clicked() && !self.windows.is_empty() {
                 for window_index in 0..self.windows.len() {
-                    self.windows[window_index].generating = true
+                    self.windows[window_index].play()
                 }
             }
 
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Pause all").clicked() {
+                    self.windows.iter_mut().for_each(WindowState::pause);
+                }
+                if ui.button("Resume all").clicked() {
+                    self.windows.iter_mut().for_each(WindowState::play);
+                }
+                if ui.button("Step all").clicked() {
+                    self.windows.iter_mut().for_each(WindowState::step_once);
+                }
+            });
+
             ui.add_space(15.0);
 
             ui.separator();
@@ -126,7 +151,7 @@ impl Main {
                 ui.selectable_value(
                     &mut self.settings.pathfinding_algorithm,
                     PathfindingAlgorithms::Astar,
-                    "Prim's algorithm",
+                    "A* algorithm",
                 );
                 ui.selectable_value(
                     &mut self.settings.pathfinding_algorithm,
@@ -147,9 +172,280 @@ impl Main {
                     "BFS algorithm",
                 );
             });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::GreedyBestFirst,
+                    "Greedy best-first",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::WeightedAstar,
+                    "Weighted A*",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::BidirectionalBfs,
+                    "Bidirectional BFS",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::BidirectionalAstar,
+                    "Bidirectional A*",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::JumpPointSearch,
+                    "Jump Point Search",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::IterativeDeepeningDfs,
+                    "Iterative deepening DFS",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::IdaStar,
+                    "IDA*",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::LeftWallFollower,
+                    "Left-hand wall follower",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::RightWallFollower,
+                    "Right-hand wall follower",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::Pledge,
+                    "Pledge",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::Tremaux,
+                    "Trémaux",
+                );
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::DeadEndFilling,
+                    "Dead-end filling",
+                );
+            });
+            ui.end_row();
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.settings.pathfinding_algorithm,
+                    PathfindingAlgorithms::DStarLite,
+                    "D* Lite (fog of war)",
+                );
+            });
+
+            ui.add_space(10.0);
+
+            ui.add_enabled_ui(self.settings.pathfinding_algorithm.uses_heuristic(), |ui| {
+                egui::ComboBox::from_label("heuristic")
+                    .selected_text(self.settings.heuristic.to_string())
+                    .show_ui(ui, |ui| {
+                        for heuristic in Heuristic::ALL {
+                            ui.selectable_value(&mut self.settings.heuristic, heuristic, heuristic.to_string());
+                        }
+                    });
+            });
+
+            ui.add_enabled_ui(self.settings.pathfinding_algorithm == PathfindingAlgorithms::WeightedAstar, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("weight (epsilon):");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.astar_weight)
+                            .range(1.0..=10.0)
+                            .speed(0.05)
+                            .max_decimals(2),
+                    );
+                });
+            });
+
+            ui.add_enabled_ui(self.settings.pathfinding_algorithm == PathfindingAlgorithms::DStarLite, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("sensor radius:");
+                    ui.add(egui::DragValue::new(&mut self.settings.sensor_radius).range(0..=50));
+                });
+            });
+
+            ui.separator();
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                ui.label("walls to open:");
+                ui.add(
+                    egui::DragValue::new(&mut self.settings.braid_fraction)
+                        .range(0.0..=1.0)
+                        .speed(0.01)
+                        .max_decimals(2),
+                );
+            });
+
+            let braid_btn = ui
+                .button("Open up walls")
+                .on_hover_text("Removes a share of the interior walls, creating loops and open areas");
+
+            if braid_btn.clicked() {
+                for window in &mut self.windows {
+                    if window.maze.is_generated() {
+                        window.maze.braid(self.settings.braid_fraction);
+                        window.solving = false;
+                        window.needs_redraw = true;
+                    }
+                }
+            }
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                ui.label("flood from:");
+                ui.add(egui::DragValue::new(&mut self.settings.flood_source.0).prefix("x "));
+                ui.add(egui::DragValue::new(&mut self.settings.flood_source.1).prefix("y "));
+            });
+
+            ui.horizontal(|ui| {
+                let distance_btn = ui
+                    .button("Distance map")
+                    .on_hover_text("Colours every cell by its distance to the flood source and shows the way back to it");
+                let hide_btn = ui.button("Hide");
+                let diameter_btn = ui
+                    .button("Find diameter")
+                    .on_hover_text("Marks the two cells farthest apart, which are then used as start and goal");
+
+                for window in &mut self.windows {
+                    if !window.maze.is_generated() {
+                        continue;
+                    }
+                    if distance_btn.clicked() {
+                        let (x, y) = self.settings.flood_source;
+                        let source = (x.min(window.maze.width - 1), y.min(window.maze.height - 1));
+                        window.maze.distance_field = Some(window.maze.distance_field(source));
+                        window.needs_redraw = true;
+                    }
+                    if hide_btn.clicked() {
+                        window.maze.distance_field = None;
+                        window.needs_redraw = true;
+                    }
+                    if diameter_btn.clicked() {
+                        window.maze.diameter = Some(window.maze.diameter());
+                        window.needs_redraw = true;
+                    }
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.checkbox(&mut self.settings.dynamic_walls, "Doors open and close while solving")
+                .on_hover_text("The pathfinder walks its plan and replans when a door shuts in front of it");
+            ui.add_enabled_ui(self.settings.dynamic_walls, |ui| {
+                egui::ComboBox::from_label("door timing")
+                    .selected_text(self.settings.door_mode.to_string())
+                    .show_ui(ui, |ui| {
+                        for mode in [DoorMode::Scheduled, DoorMode::Random] {
+                            ui.selectable_value(&mut self.settings.door_mode, mode, mode.to_string());
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("doors:");
+                    ui.add(egui::DragValue::new(&mut self.settings.door_count).range(1..=1000));
+                    ui.label("every");
+                    ui.add(egui::DragValue::new(&mut self.settings.door_period).range(1..=10000));
+                    ui.label("steps");
+                });
+            });
+
+            ui.add_space(10.0);
+
+            let solve_mazes_btn = ui.button("solve the mazes");
+
+            if solve_mazes_btn.clicked() {
+                for window in &mut self.windows {
+                    if !window.maze.is_generated() {
+                        continue; // Only fully generated mazes can be solved
+                    }
+                    let (start, goal) = window.maze.suggested_endpoints();
+                    window.maze.init_solver(
+                        self.settings.pathfinding_algorithm,
+                        self.settings.solver_options(),
+                        start,
+                        goal,
+                    );
+                    if self.settings.dynamic_walls {
+                        window.maze.enable_dynamic_walls(
+                            self.settings.door_mode,
+                            self.settings.door_count,
+                            self.settings.door_period,
+                        );
+                    } else {
+                        window.maze.disable_dynamic_walls();
+                    }
+                    window.solve_time = Duration::ZERO;
+                    window.solving = true;
+                }
+            }
+
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                let agents_btn = ui
+                    .button("solve with agents")
+                    .on_hover_text(
+                        "Cooperative A*: agents plan one after another around the cells the others have reserved. \
+                         It is not complete, so after a few priority orders it can give up without moving anyone",
+                    );
+                ui.add(egui::DragValue::new(&mut self.settings.agent_count).range(1..=100));
+                ui.label("agents");
+
+                if agents_btn.clicked() {
+                    for window in &mut self.windows {
+                        if !window.maze.is_generated() {
+                            continue;
+                        }
+                        let pairs = window.maze.random_agent_pairs(self.settings.agent_count);
+                        window.maze.init_fleet(pairs);
+                        window.solve_time = Duration::ZERO;
+                        window.solving = true;
+                    }
+                }
+            });
+
+            ui.add_space(5.0);
+
+            let race_btn = ui
+                .button("Race all algorithms")
+                .on_hover_text("Runs every pathfinder on a copy of the selected maze");
+
+            if race_btn.clicked() {
+                self.start_race();
+            }
 
             ui.add_space(15.0);
 
+            self.generate_race_leaderboard(ui);
+
             ui.separator();
             
 
@@ -157,6 +453,63 @@ impl Main {
     }
 
 
+    fn generate_race_leaderboard(&mut self, ui: &mut egui::Ui) {
+        let Some(race) = &self.race else {
+            return;
+        };
+
+        let leaderboard = race.leaderboard(&self.windows);
+        if leaderboard.is_empty() {
+            self.race = None; // Every race window has been closed
+            return;
+        }
+
+        ui.heading("Leaderboard");
+        ui.label(format!("from {:?} to {:?}", race.start, race.goal));
+
+        ui.add_space(5.0);
+
+        egui::Grid::new("race_leaderboard")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                ui.label("#");
+                ui.label("algorithm");
+                ui.label("expanded");
+                ui.label("memory");
+                ui.label("cost");
+                ui.label("optimal");
+                ui.label("time");
+                ui.end_row();
+
+                for (place, entry) in leaderboard.iter().enumerate() {
+                    ui.label(format!("{}", place + 1));
+                    ui.label(entry.algorithm.to_string());
+                    match entry.expanded_split {
+                        Some((forward, backward)) => ui.label(format!("{} ({}+{})", entry.expanded, forward, backward)),
+                        None => ui.label(entry.expanded.to_string()),
+                    };
+                    ui.label(entry.peak_memory.to_string());
+                    match (entry.finished, entry.path_cost) {
+                        (true, Some(cost)) => ui.label(cost.to_string()),
+                        (true, None) if entry.looping => ui.label("looped"),
+                        (true, None) => ui.label("no path"),
+                        (false, _) => ui.label("..."),
+                    };
+                    match entry.optimal {
+                        Some(true) => ui.label("yes"),
+                        Some(false) => ui.label("no"),
+                        None => ui.label("-"),
+                    };
+                    ui.label(format!("{:.3}s", entry.time.as_secs_f64()));
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(15.0);
+    }
+
+
     fn generate_settings_side_section(&mut self, ui: &mut egui::Ui) {
 
             ui.heading("Settings");
@@ -175,6 +528,18 @@ impl Main {
 
             ui.add_space(10.0);
 
+            ui.horizontal(|ui| {
+                ui.label("target FPS:");
+                ui.add(
+                    egui::DragValue::new(&mut self.settings.desired_fps)
+                        .range(10..=240)
+                        .speed(0.1)
+                        .max_decimals(0),
+                );
+            });
+
+            ui.add_space(10.0);
+
             ui.horizontal(|ui| {
                 ui.label("Maze Width: ");
                 ui.add(
@@ -195,6 +560,10 @@ impl Main {
                 );
             });
 
+            ui.add_space(10.0);
+
+            ui.checkbox(&mut self.settings.restore_session, "Restore windows on restart");
+
             ui.add_space(15.0);
 
             ui.separator();
@@ -205,20 +574,56 @@ impl Main {
     fn generate_info_side_section(&mut self, ui: &mut egui::Ui, integration_info: &eframe::IntegrationInfo) {
         ui.heading("Info");
 
-        if let Some(cpu_usage) = integration_info.cpu_usage {
-
-
-            let fps = (2.0 / cpu_usage + self.last_frame_fps as f32).floor();
-
-            self.last_frame_fps = cpu_usage as usize;
-            ui.label(format!("FPS: {:.2}", fps));
+        if let Some(fps) = self.scheduler.fps() {
+            ui.label(format!("FPS: {:.1} (target {})", fps, self.settings.desired_fps));
         } else {
             ui.label("FPS: N/A");
         }
+
+        if let Some(cpu_usage) = integration_info.cpu_usage {
+            ui.label(format!("CPU time per frame: {:.2}ms", cpu_usage * 1000.0));
+        }
+
+        ui.label(format!("steps this frame: {}", self.scheduler.steps_this_frame));
+
+        self.frame_time_graph(ui);
     
         ui.add_space(15.0);
         ui.separator();
 
         ui.label(format!("selected window: {:?}", self.selected_window_id));
     }
+
+
+    /// Plots the recent frame times against the target frame time.
+    fn frame_time_graph(&self, ui: &mut egui::Ui) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 60.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let frame_times = self.scheduler.frame_times();
+        let target = FrameScheduler::frame_interval(self.settings.desired_fps).as_secs_f32();
+
+        // Scale so the target sits in the middle, leaving room for slow frames
+        let max_time = frame_times.iter().copied().fold(target * 2.0, f32::max);
+        let to_y = |time: f32| rect.bottom() - (time / max_time) * rect.height();
+
+        painter.hline(
+            rect.x_range(),
+            to_y(target),
+            egui::Stroke::new(1.0, egui::Color32::DARK_GREEN),
+        );
+
+        let step = rect.width() / frame_times.len().max(2) as f32;
+        let points: Vec<egui::Pos2> = frame_times
+            .iter()
+            .enumerate()
+            .map(|(i, &time)| egui::pos2(rect.left() + i as f32 * step, to_y(time)))
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, egui::Color32::LIGHT_RED)));
+
+        if let Some(&last) = frame_times.back() {
+            ui.label(format!("frame time: {:.1}ms", last * 1000.0));
+        }
+    }
 }
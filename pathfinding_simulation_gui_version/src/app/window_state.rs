@@ -1,35 +1,140 @@
 use std::time::Duration;
 
+use crate::app::maze::{CellMark, MazeGenerator, MazeStats, Topology, Validation, WallChange};
 use crate::app::Maze;
+use egui::ecolor::Hsva;
 use egui::{Color32, TextureHandle, TextureOptions};
+use serde::{Deserialize, Serialize};
 
+/// Whether a window's generation is advancing on its own every frame.
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Playback {
+    Playing,
+    #[default]
+    Paused,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct WindowState {
     pub id: usize,        // Unique identifier for the window
     pub title: String,    // Title of the window
     pub is_open: bool,    // Whether the window is open
 
-    pub generating: bool, // Whether the maze is currently being generated
+    #[serde(skip)]
+    pub playback: Playback, // Whether the maze generation is playing or paused
     pub maze: Maze,       // The maze data
 
+    #[serde(skip)]
     pub maze_texture: Option<TextureHandle>, // Cached texture of the maze
+    #[serde(skip)]
     pub needs_redraw: bool,                  // Flag indicating if the maze needs to be redrawn
 
     pub generation_time: Duration,
 
+    #[serde(skip)]
+    pub solving: bool,        // Whether a pathfinder is currently running
+    #[serde(skip)]
+    pub solve_time: Duration,
+
+    #[serde(skip)]
+    pub flashing: Vec<(WallChange, usize)>, // Recently toggled doors and the frames left to flash them
+
+    #[serde(default)]
+    pub show_internals: bool, // Whether the generator's working state is drawn over the maze
+
+    #[serde(skip)]
+    validation: Option<(usize, Validation)>, // Last validation, with the maze revision it was made on
+    #[serde(skip)]
+    stats: Option<(StatsKey, MazeStats)>, // Last statistics and what they were measured on
 }
 
+/// The maze revision and the start and goal that statistics were measured with.
+type StatsKey = (usize, ((usize, usize), (usize, usize)));
+
+/// Frames a toggled door keeps flashing for.
+const FLASH_FRAMES: usize = 40;
+
 impl WindowState {
     pub fn new(id: usize, title: String, maze: Maze) -> Self {
         Self {
             id,
             title,
             is_open: true,
-            generating: false,
+            playback: Playback::Paused,
             maze,
             maze_texture: None,
             needs_redraw: true,
             generation_time: Duration::ZERO,
+            solving: false,
+            solve_time: Duration::ZERO,
+            flashing: Vec::new(),
+            show_internals: true,
+            validation: None,
+            stats: None,
+        }
+    }
+
+    /// The maze's validation, only redone once the grid has changed since the last one.
+    pub fn validation(&mut self) -> &Validation {
+        let revision = self.maze.revision();
+        if self.validation.as_ref().map_or(true, |(at, _)| *at != revision) {
+            self.validation = Some((revision, self.maze.validate()));
         }
+        &self.validation.as_ref().expect("validation was just made").1
+    }
+
+    /// Statistics of the maze between its suggested start and goal, only measured
+    /// again once the grid or the endpoints have changed.
+    pub fn stats(&mut self) -> &MazeStats {
+        let key = (self.maze.revision(), self.maze.suggested_endpoints());
+        if self.stats.as_ref().map_or(true, |(at, _)| *at != key) {
+            let (start, goal) = key.1;
+            self.stats = Some((key, self.maze.analyze(start, goal)));
+        }
+        &self.stats.as_ref().expect("statistics were just measured").1
+    }
+
+    /// Starts flashing the doors toggled since the last frame and counts down
+    /// the ones already flashing.
+    pub fn update_flashing(&mut self) {
+        self.flashing.retain_mut(|(_, frames)| {
+            *frames -= 1;
+            *frames > 0
+        });
+        let changes = self.maze.take_wall_changes();
+        self.flashing.extend(changes.into_iter().map(|change| (change, FLASH_FRAMES)));
+        if !self.flashing.is_empty() {
+            self.needs_redraw = true;
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback == Playback::Playing
+    }
+
+    /// Resumes generation, unless the maze is already fully generated.
+    pub fn play(&mut self) {
+        if !self.maze.is_generated() {
+            self.playback = Playback::Playing;
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.playback = Playback::Paused;
+    }
+
+    /// Pauses and advances the generation by a single step.
+    pub fn step_once(&mut self) {
+        self.pause();
+        self.maze.step(1, &mut self.generation_time);
+        self.needs_redraw = true;
+    }
+
+    /// Pauses and moves the generation to the given point in its history.
+    pub fn seek(&mut self, target: usize) {
+        self.pause();
+        self.maze.seek(target);
+        self.needs_redraw = true;
     }
 
     pub fn generate_maze_texture(&mut self, ctx: &egui::Context, size: [usize; 2]) {
@@ -53,32 +158,201 @@ impl WindowState {
         self.needs_redraw = false;
     }
 
-    fn render_maze_to_image(&self, image: &mut egui::ColorImage) {
+    pub fn render_maze_to_image(&self, image: &mut egui::ColorImage) {
         let maze_width = self.maze.width;
         let maze_height = self.maze.height;
 
         let pixels_per_cell_x = image.size[0] as f32 / maze_width as f32;
         let pixels_per_cell_y = image.size[1] as f32 / maze_height as f32;
+        // Hex cells can't be drawn one rectangle at a time, so their colours are collected and painted after
+        let hex = (self.maze.grid.topology() == Topology::Hex).then(|| HexLayout::new(&self.maze, image.size));
+        let mut hex_cells = Vec::new();
 
         let wall_color = Color32::BLACK;
         let visited_color = Color32::WHITE;
         let unvisited_color = Color32::GRAY;
+        let open_color = Color32::from_rgb(255, 230, 140);
+        let closed_color = Color32::from_rgb(170, 200, 255);
+        let backward_open_color = Color32::from_rgb(230, 170, 255);
+        let backward_closed_color = Color32::from_rgb(200, 160, 220);
+        let scanned_color = Color32::from_rgb(215, 225, 235);
+        let path_color = Color32::from_rgb(255, 140, 60);
+        let start_color = Color32::from_rgb(60, 200, 90);
+        let goal_color = Color32::from_rgb(220, 50, 50);
+        let fog_color = Color32::from_rgb(90, 90, 100);
+        let fog_wall_color = Color32::from_rgb(120, 120, 130);
+        let planned_color = Color32::from_rgb(255, 210, 80);
+        let agent_color = Color32::from_rgb(40, 120, 220);
+        let flow_color = Color32::from_rgb(40, 40, 40);
+        let stack_color = Color32::from_rgb(200, 180, 240);
+        let frontier_color = Color32::from_rgb(255, 200, 120);
+
+        // What the generator is working with, while the grid shows its latest state: the
+        // depth first search's stack up to the current cell, the cells on Prim's frontier,
+        // the Aldous-Broder walker, and Kruskal's forest with each set in a colour of its own
+        let generator = self.maze.generator.as_ref().filter(|_| self.show_internals && self.maze.is_live());
+        let mut internal_cells = vec![None; maze_width * maze_height];
+        let mut kruskal = None;
+        match generator {
+            Some(MazeGenerator::Dfs { stack }) => {
+                for &(x, y) in stack {
+                    internal_cells[y * maze_width + x] = Some(stack_color);
+                }
+                if let Some(&(x, y)) = stack.last() {
+                    internal_cells[y * maze_width + x] = Some(agent_color);
+                }
+            }
+            Some(MazeGenerator::Prims { walls }) => {
+                for &(_, _, x, y) in walls {
+                    // Walls into cells that were reached some other way are stale, and skipped when picked
+                    if !self.maze.grid.visited(x, y) {
+                        internal_cells[y * maze_width + x] = Some(frontier_color);
+                    }
+                }
+            }
+            Some(MazeGenerator::AldousBroder { current: (x, y), .. }) => {
+                internal_cells[y * maze_width + x] = Some(agent_color);
+            }
+            Some(MazeGenerator::Kruskal { sets, candidate, .. }) => kruskal = Some((sets, candidate)),
+            None => {}
+        }
+
+        let explorer = self.maze.solver.as_ref().and_then(|solver| solver.explorer.as_ref());
+        let execution = self.maze.solver.as_ref().and_then(|solver| solver.execution.as_ref());
+        let mut walked = vec![false; maze_width * maze_height];
+        for &(x, y) in execution.map_or(&[][..], |execution| &execution.walked) {
+            walked[y * maze_width + x] = true;
+        }
+
+        // Each agent of a fleet gets its own colour, its goal a paler shade of it
+        let agent_colors = [
+            Color32::from_rgb(40, 120, 220),
+            Color32::from_rgb(220, 60, 60),
+            Color32::from_rgb(50, 170, 80),
+            Color32::from_rgb(230, 150, 30),
+            Color32::from_rgb(150, 70, 200),
+            Color32::from_rgb(30, 170, 170),
+            Color32::from_rgb(210, 80, 160),
+            Color32::from_rgb(120, 100, 60),
+        ];
+        let mut fleet_cells = vec![None; maze_width * maze_height];
+        if let Some(fleet) = &self.maze.fleet {
+            for (agent, color) in fleet.agents.iter().zip(agent_colors.iter().cycle()) {
+                let pale = Color32::from_rgb(
+                    color.r() / 3 + 170,
+                    color.g() / 3 + 170,
+                    color.b() / 3 + 170,
+                );
+                fleet_cells[agent.goal.1 * maze_width + agent.goal.0] = Some(pale);
+            }
+            for (agent, &color) in fleet.agents.iter().zip(agent_colors.iter().cycle()) {
+                let (x, y) = fleet.position(agent);
+                fleet_cells[y * maze_width + x] = Some(color);
+            }
+        }
 
         for y in 0..maze_height {
             for x in 0..maze_width {
-                let node = &self.maze.grid[y][x];
-
                 let x_pos = (x as f32 * pixels_per_cell_x) as usize;
                 let y_pos = (y as f32 * pixels_per_cell_y) as usize;
                 let x_end = ((x + 1) as f32 * pixels_per_cell_x) as usize;
                 let y_end = ((y + 1) as f32 * pixels_per_cell_y) as usize;
 
                 // Determine the fill color for the cell
-                let fill_color = if node.visited {
+                let mut fill_color = if self.maze.grid.visited(x, y) {
                     visited_color
                 } else {
                     unvisited_color
                 };
+                if let Some((sets, _)) = kruskal {
+                    if self.maze.grid.visited(x, y) {
+                        fill_color = set_color(sets.root(y * maze_width + x));
+                    }
+                }
+                if let Some(color) = internal_cells[y * maze_width + x] {
+                    fill_color = color;
+                }
+
+                // The distance map shades each reachable cell by how far it is from the source
+                if let Some(field) = &self.maze.distance_field {
+                    if (x, y) == field.source {
+                        fill_color = wall_color;
+                    } else if let Some(distance) = field.distance[y * maze_width + x] {
+                        fill_color = heat_color(distance as f32 / field.max.max(1) as f32);
+                    }
+                }
+
+                // The diameter's ends are suggested as start and goal until a solver runs
+                if let (Some(diameter), None) = (&self.maze.diameter, &self.maze.solver) {
+                    if (x, y) == diameter.ends.0 {
+                        fill_color = start_color;
+                    } else if (x, y) == diameter.ends.1 {
+                        fill_color = goal_color;
+                    }
+                }
+
+                // Overlay the state of the pathfinder, if one has been started
+                if let Some(solver) = &self.maze.solver {
+                    if (x, y) == solver.start {
+                        fill_color = start_color;
+                    } else if (x, y) == solver.goal {
+                        fill_color = goal_color;
+                    } else {
+                        let index = y * maze_width + x;
+                        let forward_mark = solver.forward.marks[index];
+                        let backward_mark = solver
+                            .backward
+                            .as_ref()
+                            .map_or(CellMark::Unseen, |backward| backward.marks[index]);
+
+                        // The path wins, then the search from the goal, then the one from the start
+                        fill_color = match (forward_mark, backward_mark) {
+                            (CellMark::Path, _) => path_color,
+                            (_, CellMark::Closed) => backward_closed_color,
+                            (_, CellMark::Open) => backward_open_color,
+                            (CellMark::Closed, _) => closed_color,
+                            (CellMark::Open, _) => open_color,
+                            (CellMark::Scanned, _) => scanned_color,
+                            _ => fill_color,
+                        };
+
+                        // A plan being walked shows the route taken so far and who is walking it
+                        if let Some(execution) = execution {
+                            if (x, y) == execution.position {
+                                fill_color = agent_color;
+                            } else if walked[index] {
+                                fill_color = path_color;
+                            }
+                        }
+
+                        // Under fog of war, show what the agent knows and where it is heading
+                        if let Some(explorer) = explorer {
+                            if (x, y) == explorer.position {
+                                fill_color = agent_color;
+                            } else if explorer.planned[index] && forward_mark != CellMark::Path {
+                                fill_color = planned_color;
+                            } else if !explorer.known[index] {
+                                fill_color = fog_color;
+                            }
+                        }
+                    }
+                }
+
+                if let Some(color) = fleet_cells[y * maze_width + x] {
+                    fill_color = color;
+                }
+
+                // Walls the agent has not seen yet are drawn faintly
+                let wall_color = match explorer {
+                    Some(explorer) if !explorer.known[y * maze_width + x] => fog_wall_color,
+                    _ => wall_color,
+                };
+
+                if hex.is_some() {
+                    hex_cells.push((fill_color, wall_color));
+                    continue;
+                }
+                let walls = self.maze.grid.walls(x, y);
 
                 // Fill the cell with the determined color
                 for yi in y_pos..y_end {
@@ -94,7 +368,7 @@ impl WindowState {
                 }
 
                 // Draw walls onto the image buffer, only if this cell is "responsible"
-                if node.walls[0] && y == 0 {
+                if walls[0] && y == 0 {
                     // Top wall
                     for xi in x_pos..x_end {
                         if y_pos < image.size[1] && xi < image.size[0] {
@@ -102,7 +376,7 @@ impl WindowState {
                         }
                     }
                 }
-                if node.walls[3] && x == 0 {
+                if walls[3] && x == 0 {
                     // Left wall
                     for yi in y_pos..y_end {
                         if yi < image.size[1] && x_pos < image.size[0] {
@@ -110,7 +384,7 @@ impl WindowState {
                         }
                     }
                 }
-                if node.walls[1] {
+                if walls[1] {
                     // Right wall (only for last column)
                     for yi in y_pos..y_end {
                         if yi < image.size[1] && x_end - 1 < image.size[0] {
@@ -118,7 +392,7 @@ impl WindowState {
                         }
                     }
                 }
-                if node.walls[2] {
+                if walls[2] {
                     // Bottom wall (only for last row)
                     for xi in x_pos..x_end {
                         if y_end - 1 < image.size[1] && xi < image.size[0] {
@@ -128,5 +402,296 @@ impl WindowState {
                 }
             }
         }
+
+        if let Some(layout) = &hex {
+            layout.paint_cells(image, &self.maze, &hex_cells);
+        }
+        let center = |(x, y): (usize, usize)| match &hex {
+            Some(layout) => layout.center((x, y)),
+            None => ((x as f32 + 0.5) * pixels_per_cell_x, (y as f32 + 0.5) * pixels_per_cell_y),
+        };
+
+        // The flow field points every cell toward the source, once cells are big enough to show it
+        if let Some(field) = &self.maze.distance_field {
+            if pixels_per_cell_x >= 8.0 && pixels_per_cell_y >= 8.0 {
+                for index in 0..maze_width * maze_height {
+                    let (x, y) = (index % maze_width, index / maze_width);
+                    let Some(next) = field.next_step(&self.maze, (x, y)) else {
+                        continue;
+                    };
+                    let (center_x, center_y) = center((x, y));
+                    let (next_x, next_y) = center(next);
+                    let length = (next_x - center_x).hypot(next_y - center_y);
+                    let (dx, dy) = ((next_x - center_x) / length, (next_y - center_y) / length);
+                    let reach = (pixels_per_cell_x.min(pixels_per_cell_y) * 0.4) as usize;
+                    for step in 0..reach {
+                        let xi = (center_x + dx * step as f32) as usize;
+                        let yi = (center_y + dy * step as f32) as usize;
+                        if xi < image.size[0] && yi < image.size[1] {
+                            image.pixels[yi * image.size[0] + xi] = flow_color;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Doors that just opened or closed blink, green when open and red when closed
+        for &(change, frames) in &self.flashing {
+            if (frames / 5) % 2 == 1 {
+                continue;
+            }
+            let color = if change.open { start_color } else { goal_color };
+            match &hex {
+                Some(layout) => layout.paint_wall(image, change, color),
+                None => paint_wall(image, change, pixels_per_cell_x, pixels_per_cell_y, color),
+            }
+        }
+
+        // The edge Kruskal's just looked at, green if it joined two sets and red if it was rejected
+        if let Some((_, Some(candidate))) = kruskal {
+            let color = if candidate.open { start_color } else { goal_color };
+            match &hex {
+                Some(layout) => layout.paint_wall(image, *candidate, color),
+                None => paint_wall(image, *candidate, pixels_per_cell_x, pixels_per_cell_y, color),
+            }
+        }
+    }
+}
+
+/// Draws the right or bottom wall of a cell two pixels wide.
+fn paint_wall(
+    image: &mut egui::ColorImage,
+    change: WallChange,
+    pixels_per_cell_x: f32,
+    pixels_per_cell_y: f32,
+    color: Color32,
+) {
+    let x_end = ((change.x + 1) as f32 * pixels_per_cell_x) as usize;
+    let y_end = ((change.y + 1) as f32 * pixels_per_cell_y) as usize;
+    if change.wall == 1 {
+        let y_pos = (change.y as f32 * pixels_per_cell_y) as usize;
+        for yi in y_pos..y_end.min(image.size[1]) {
+            for xi in x_end.saturating_sub(1)..(x_end + 1).min(image.size[0]) {
+                image.pixels[yi * image.size[0] + xi] = color;
+            }
+        }
+    } else {
+        let x_pos = (change.x as f32 * pixels_per_cell_x) as usize;
+        for yi in y_end.saturating_sub(1)..(y_end + 1).min(image.size[1]) {
+            for xi in x_pos..x_end.min(image.size[0]) {
+                image.pixels[yi * image.size[0] + xi] = color;
+            }
+        }
+    }
+}
+
+/// Where hex cells land in the texture. Like square cells they are stretched
+/// to fill it, so a cell is `column` pixels wide and rows are `row` pixels apart.
+struct HexLayout {
+    column: f32,
+    row: f32,
+}
+
+impl HexLayout {
+    fn new(maze: &Maze, size: [usize; 2]) -> Self {
+        // Odd rows stick out half a cell to the right, and the points of the
+        // first and last rows a third of a row beyond their centres. The last
+        // pixel is left over, so walls on the right and bottom edges still fit.
+        Self {
+            column: size[0].saturating_sub(1) as f32 / (maze.width as f32 + 0.5),
+            row: size[1].saturating_sub(1) as f32 / (maze.height as f32 + 1.0 / 3.0),
+        }
+    }
+
+    fn center(&self, (x, y): (usize, usize)) -> (f32, f32) {
+        let shift = (y % 2) as f32 * 0.5;
+        ((x as f32 + 0.5 + shift) * self.column, (y as f32 + 2.0 / 3.0) * self.row)
+    }
+
+    /// Corners are counted clockwise from the top, so wall n runs from corner n to corner n + 1.
+    fn corner(&self, cell: (usize, usize), corner: usize) -> (f32, f32) {
+        let (center_x, center_y) = self.center(cell);
+        let angle = (corner as f32 * 60.0 - 90.0).to_radians();
+        // The corners sit on a circle squashed to fit the column width and row spacing
+        (
+            center_x + angle.cos() * self.column / 3f32.sqrt(),
+            center_y + angle.sin() * self.row * 2.0 / 3.0,
+        )
+    }
+
+    /// The cell covering a pixel, found by rounding in cube coordinates.
+    fn cell_at(&self, maze: &Maze, pixel_x: f32, pixel_y: f32) -> Option<(usize, usize)> {
+        // Measured in cell radii from the centre of the first cell
+        let unit_x = (pixel_x / self.column - 0.5) * 3f32.sqrt();
+        let unit_y = (pixel_y / self.row - 2.0 / 3.0) * 1.5;
+        let q = unit_x / 3f32.sqrt() - unit_y / 3.0;
+        let r = unit_y * 2.0 / 3.0;
+
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), (-q - r).round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs + q + r).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        // Back from axial coordinates to columns, odd rows being shifted right
+        let (q, r) = (rq as isize, rr as isize);
+        let x = usize::try_from(q + r.div_euclid(2)).ok().filter(|&x| x < maze.width)?;
+        let y = usize::try_from(r).ok().filter(|&y| y < maze.height)?;
+        Some((x, y))
+    }
+
+    /// Fills every cell with its colour and draws its standing walls in the colour given with it.
+    fn paint_cells(&self, image: &mut egui::ColorImage, maze: &Maze, cells: &[(Color32, Color32)]) {
+        for yi in 0..image.size[1] {
+            for xi in 0..image.size[0] {
+                image.pixels[yi * image.size[0] + xi] = match self.cell_at(maze, xi as f32 + 0.5, yi as f32 + 0.5) {
+                    Some((x, y)) => cells[y * maze.width + x].0,
+                    None => Color32::WHITE,
+                };
+            }
+        }
+
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                let wall_color = cells[y * maze.width + x].1;
+                for wall in (0..6).filter(|&wall| maze.grid.wall(x, y, wall)) {
+                    self.paint_line(image, self.corner((x, y), wall), self.corner((x, y), wall + 1), 0, wall_color);
+                }
+            }
+        }
+    }
+
+    /// Draws a wall two pixels wide.
+    fn paint_wall(&self, image: &mut egui::ColorImage, change: WallChange, color: Color32) {
+        let cell = (change.x, change.y);
+        self.paint_line(image, self.corner(cell, change.wall), self.corner(cell, change.wall + 1), 1, color);
+    }
+
+    /// A line from one point to another, widened by `spread` pixels on every side.
+    fn paint_line(&self, image: &mut egui::ColorImage, from: (f32, f32), to: (f32, f32), spread: usize, color: Color32) {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f32 / steps as f32;
+            let x = (from.0 + (to.0 - from.0) * t) as usize;
+            let y = (from.1 + (to.1 - from.1) * t) as usize;
+            for yi in y.saturating_sub(spread)..(y + spread + 1).min(image.size[1]) {
+                for xi in x.saturating_sub(spread)..(x + spread + 1).min(image.size[0]) {
+                    image.pixels[yi * image.size[0] + xi] = color;
+                }
+            }
+        }
+    }
+}
+
+/// A pale colour picked from the set's root, spread around the colour wheel by
+/// the golden ratio so neighbouring roots look different.
+fn set_color(root: usize) -> Color32 {
+    let hue = (root as f32 * 0.618_034).fract();
+    Hsva::new(hue, 0.35, 0.95, 1.0).into()
+}
+
+/// Blue for cells next to the source through yellow to red for the farthest ones.
+fn heat_color(t: f32) -> Color32 {
+    let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t) as u8;
+    let (from, to, t) = if t < 0.5 {
+        ((70, 130, 230), (250, 230, 90), t * 2.0)
+    } else {
+        ((250, 230, 90), (220, 50, 40), t * 2.0 - 1.0)
+    };
+    Color32::from_rgb(lerp(from.0, to.0, t), lerp(from.1, to.1, t), lerp(from.2, to.2, t))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn center_pixel(window: &WindowState, (x, y): (usize, usize)) -> Color32 {
+        let mut image = egui::ColorImage::new([40, 40], Color32::WHITE);
+        window.render_maze_to_image(&mut image);
+        image.pixels[(y * 10 + 5) * 40 + x * 10 + 5]
+    }
+
+    #[test]
+    fn test_internals_overlay_shows_current_cell() {
+        let mut maze = Maze::new(4, 4);
+        maze.init_dfs();
+        let mut generation_time = Duration::ZERO;
+        maze.step(5, &mut generation_time);
+        let Some(MazeGenerator::Dfs { stack }) = &maze.generator else {
+            panic!("generation should still be running");
+        };
+        let current = *stack.last().unwrap();
+
+        let mut window = WindowState::new(0, String::from("test"), maze);
+        assert_eq!(center_pixel(&window, current), Color32::from_rgb(40, 120, 220));
+
+        window.show_internals = false;
+        assert_eq!(center_pixel(&window, current), Color32::WHITE);
+    }
+
+    #[test]
+    fn test_validation_is_redone_only_after_the_grid_changes() {
+        let mut maze = Maze::new(6, 6);
+        maze.init_kruskals();
+        let mut generation_time = Duration::ZERO;
+        while maze.step(1000, &mut generation_time) {}
+
+        let mut window = WindowState::new(0, String::from("test"), maze);
+        assert!(window.validation().is_perfect());
+        let revision = window.maze.revision();
+        assert!(window.validation().is_perfect());
+        assert_eq!(window.maze.revision(), revision);
+
+        window.maze.braid(1.0);
+        assert_ne!(window.maze.revision(), revision);
+        assert_eq!(window.validation().cycles, 25); // Every interior wall is gone
+    }
+
+    #[test]
+    fn test_stats_follow_the_suggested_endpoints() {
+        let mut maze = Maze::new(8, 8);
+        maze.init_prims();
+        let mut generation_time = Duration::ZERO;
+        while maze.step(1000, &mut generation_time) {}
+
+        let mut window = WindowState::new(0, String::from("test"), maze);
+        let corners = window.maze.shortest_path_cost((0, 0), (7, 7));
+        assert_eq!(window.stats().solution_length, corners);
+
+        // Finding the diameter moves start and goal, so the solution is measured again
+        window.maze.diameter = Some(window.maze.diameter());
+        let ends = window.maze.suggested_endpoints();
+        let diameter = window.maze.shortest_path_cost(ends.0, ends.1);
+        assert_eq!(window.stats().solution_length, diameter);
+    }
+
+    #[test]
+    fn test_hex_cells_are_painted_around_their_centres() {
+        let mut maze = Maze::with_topology(7, 5, Topology::Hex);
+        maze.init_dfs();
+        let mut generation_time = Duration::ZERO;
+        maze.step(8, &mut generation_time);
+        let Some(MazeGenerator::Dfs { stack }) = &maze.generator else {
+            panic!("generation should still be running");
+        };
+        let current = *stack.last().unwrap();
+
+        let mut image = egui::ColorImage::new([150, 100], Color32::WHITE);
+        let layout = HexLayout::new(&maze, image.size);
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                let (center_x, center_y) = layout.center((x, y));
+                assert_eq!(layout.cell_at(&maze, center_x, center_y), Some((x, y)));
+            }
+        }
+        assert_eq!(layout.cell_at(&maze, 1.0, 1.0), None); // Left of the first row's point
+
+        let window = WindowState::new(0, String::from("test"), maze);
+        window.render_maze_to_image(&mut image);
+        let (center_x, center_y) = layout.center(current);
+        assert_eq!(image.pixels[center_y as usize * 150 + center_x as usize], Color32::from_rgb(40, 120, 220));
     }
 }
@@ -0,0 +1,335 @@
+// bench.rs
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use crate::app::{Maze, MazeAlgorithms, PathfindingAlgorithms, SolverOptions};
+
+// Headless benchmark: every combination of size, seed, generator and
+// pathfinder is generated and solved without the GUI, and the measurements
+// are written out as CSV and/or JSON with a summary on stdout. Seeding makes
+// every run repeatable, so two builds can be compared maze for maze.
+
+const USAGE: &str = "usage: bench [--sizes 25x25,50x50] [--seeds 1,2,3] [--generators prims,dfs|all]
+             [--solvers astar,bfs|all] [--braid 0.1] [--csv FILE] [--json FILE]";
+
+/// Steps handed to the generator and solver at a time, large enough that the
+/// per-call overhead disappears from the timings.
+const STEPS: usize = 10_000;
+
+pub struct BenchConfig {
+    pub sizes: Vec<(usize, usize)>,
+    pub seeds: Vec<u64>,
+    pub generators: Vec<MazeAlgorithms>,
+    pub solvers: Vec<PathfindingAlgorithms>,
+    pub braid: f32, // Share of walls knocked down after generating, so loops can be benchmarked too
+    pub csv: Option<String>,
+    pub json: Option<String>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            sizes: vec![(25, 25), (50, 50), (100, 100)],
+            seeds: vec![1, 2, 3],
+            generators: MazeAlgorithms::ALL.to_vec(),
+            solvers: PathfindingAlgorithms::ALL.to_vec(),
+            braid: 0.0,
+            csv: None,
+            json: None,
+        }
+    }
+}
+
+/// One pathfinder run on one generated maze.
+#[derive(Clone, Debug)]
+pub struct BenchRow {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub generator: MazeAlgorithms,
+    pub solver: PathfindingAlgorithms,
+    pub generation_time: Duration, // The same for every solver run on a maze
+    pub solve_time: Duration,
+    pub expanded: usize,
+    pub path_length: Option<usize>, // None when the solver gave up or walked in circles
+    pub peak_memory: usize,
+}
+
+/// Name used on the command line and in the output, e.g. `aldousbroder` or `idastar`.
+fn slug(name: impl std::fmt::Debug) -> String {
+    format!("{:?}", name).to_lowercase()
+}
+
+fn parse_list<T: Copy + std::fmt::Debug>(value: &str, all: &[T], what: &str) -> Result<Vec<T>, String> {
+    if value == "all" {
+        return Ok(all.to_vec());
+    }
+    value
+        .split(',')
+        .map(|name| {
+            all.iter()
+                .copied()
+                .find(|&item| slug(item) == name.trim().to_lowercase())
+                .ok_or_else(|| {
+                    let known: Vec<String> = all.iter().map(|&item| slug(item)).collect();
+                    format!("unknown {} '{}', expected one of: {}", what, name, known.join(", "))
+                })
+        })
+        .collect()
+}
+
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value.split_once('x').ok_or_else(|| format!("size '{}' should look like 50x50", value))?;
+    match (width.trim().parse(), height.trim().parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(format!("size '{}' should look like 50x50", value)),
+    }
+}
+
+/// Reads the options following `bench`. Anything not given keeps its default.
+pub fn parse_args(args: &[String]) -> Result<BenchConfig, String> {
+    let mut config = BenchConfig::default();
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            return Err(USAGE.to_string());
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--sizes" => config.sizes = value.split(',').map(parse_size).collect::<Result<_, _>>()?,
+            "--seeds" => {
+                config.seeds = value
+                    .split(',')
+                    .map(|seed| seed.trim().parse().map_err(|_| format!("seed '{}' is not a number", seed)))
+                    .collect::<Result<_, _>>()?
+            }
+            "--generators" => config.generators = parse_list(value, &MazeAlgorithms::ALL, "generator")?,
+            "--solvers" => config.solvers = parse_list(value, &PathfindingAlgorithms::ALL, "solver")?,
+            "--braid" => {
+                config.braid = value
+                    .parse()
+                    .ok()
+                    .filter(|braid| (0.0..=1.0).contains(braid))
+                    .ok_or_else(|| format!("braid '{}' should be between 0 and 1", value))?
+            }
+            "--csv" => config.csv = Some(value.clone()),
+            "--json" => config.json = Some(value.clone()),
+            _ => return Err(format!("unknown option '{}'\n{}", flag, USAGE)),
+        }
+    }
+    Ok(config)
+}
+
+/// Generates every maze once and runs every solver on a copy of it.
+pub fn run(config: &BenchConfig) -> Vec<BenchRow> {
+    let mut rows = Vec::new();
+
+    for &(width, height) in &config.sizes {
+        for &seed in &config.seeds {
+            for &generator in &config.generators {
+                let mut maze = Maze::new(width, height);
+                maze.stop_recording(); // Never scrubbed through, and it would skew the timings
+                maze.seed(seed);
+                maze.init_generator(generator);
+                let mut generation_time = Duration::ZERO;
+                while maze.step(STEPS, &mut generation_time) {}
+                if config.braid > 0.0 {
+                    maze.braid(config.braid);
+                }
+
+                for &solver in &config.solvers {
+                    let mut maze = maze.clone();
+                    maze.init_solver(solver, SolverOptions::default(), (0, 0), (width - 1, height - 1));
+                    let mut solve_time = Duration::ZERO;
+                    while maze.solve_step(STEPS, &mut solve_time) {}
+
+                    let result = maze.solver.as_ref().expect("solver was just started");
+                    rows.push(BenchRow {
+                        width,
+                        height,
+                        seed,
+                        generator,
+                        solver,
+                        generation_time,
+                        solve_time,
+                        expanded: result.expanded(),
+                        path_length: result.path_cost(),
+                        peak_memory: result.peak_memory(),
+                    });
+                }
+            }
+        }
+    }
+    rows
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn to_csv(rows: &[BenchRow]) -> String {
+    let mut csv =
+        String::from("width,height,seed,generator,solver,generation_ms,solve_ms,expanded,path_length,peak_memory\n");
+    for row in rows {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{:.4},{:.4},{},{},{}",
+            row.width,
+            row.height,
+            row.seed,
+            slug(row.generator),
+            slug(row.solver),
+            millis(row.generation_time),
+            millis(row.solve_time),
+            row.expanded,
+            row.path_length.map_or(String::new(), |length| length.to_string()),
+            row.peak_memory,
+        );
+    }
+    csv
+}
+
+pub fn to_json(rows: &[BenchRow]) -> String {
+    let entries: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "  {{\"width\": {}, \"height\": {}, \"seed\": {}, \"generator\": \"{}\", \"solver\": \"{}\", \
+                 \"generation_ms\": {:.4}, \"solve_ms\": {:.4}, \"expanded\": {}, \"path_length\": {}, \
+                 \"peak_memory\": {}}}",
+                row.width,
+                row.height,
+                row.seed,
+                slug(row.generator),
+                slug(row.solver),
+                millis(row.generation_time),
+                millis(row.solve_time),
+                row.expanded,
+                row.path_length.map_or("null".to_string(), |length| length.to_string()),
+                row.peak_memory,
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Averages per generator and solver over every size and seed.
+pub fn summary(rows: &[BenchRow]) -> String {
+    let mut groups: BTreeMap<(String, String), Vec<&BenchRow>> = BTreeMap::new();
+    for row in rows {
+        groups.entry((slug(row.generator), slug(row.solver))).or_default().push(row);
+    }
+
+    let mut table = format!(
+        "{:<14} {:<22} {:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>7}\n",
+        "generator", "solver", "runs", "gen ms", "solve ms", "expanded", "memory", "path", "solved"
+    );
+    for ((generator, solver), group) in &groups {
+        let runs = group.len() as f64;
+        let mean = |value: &dyn Fn(&BenchRow) -> f64| group.iter().map(|row| value(row)).sum::<f64>() / runs;
+        let solved: Vec<usize> = group.iter().filter_map(|row| row.path_length).collect();
+        let path = if solved.is_empty() {
+            "-".to_string()
+        } else {
+            format!("{:.1}", solved.iter().sum::<usize>() as f64 / solved.len() as f64)
+        };
+        let _ = writeln!(
+            table,
+            "{:<14} {:<22} {:>5} {:>10.3} {:>10.3} {:>10.1} {:>10.1} {:>10} {:>3}/{:<3}",
+            generator,
+            solver,
+            group.len(),
+            mean(&|row| millis(row.generation_time)),
+            mean(&|row| millis(row.solve_time)),
+            mean(&|row| row.expanded as f64),
+            mean(&|row| row.peak_memory as f64),
+            path,
+            solved.len(),
+            group.len(),
+        );
+    }
+    table
+}
+
+/// Entry point for `bench`, returns the process exit code.
+pub fn main(args: &[String]) -> i32 {
+    let config = match parse_args(args) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            return 2;
+        }
+    };
+
+    let runs = config.sizes.len() * config.seeds.len() * config.generators.len() * config.solvers.len();
+    eprintln!("Running {} benchmarks...", runs);
+    let rows = run(&config);
+
+    for (path, contents) in [(&config.csv, to_csv(&rows)), (&config.json, to_json(&rows))] {
+        let Some(path) = path else {
+            continue;
+        };
+        if let Err(e) = std::fs::write(path, contents) {
+            eprintln!("Failed to write {}: {}", path, e);
+            return 1;
+        }
+        eprintln!("Results saved as {}", path);
+    }
+
+    print!("{}", summary(&rows));
+    0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let config = parse_args(&args("--sizes 10x20,30x30 --seeds 4,5 --generators prims,AldousBroder --solvers idastar --braid 0.25 --csv out.csv")).unwrap();
+        assert_eq!(config.sizes, vec![(10, 20), (30, 30)]);
+        assert_eq!(config.seeds, vec![4, 5]);
+        assert_eq!(config.generators, vec![MazeAlgorithms::Prims, MazeAlgorithms::AldousBroder]);
+        assert_eq!(config.solvers, vec![PathfindingAlgorithms::IdaStar]);
+        assert_eq!(config.braid, 0.25);
+        assert_eq!(config.csv.as_deref(), Some("out.csv"));
+        assert_eq!(config.json, None);
+
+        assert!(parse_args(&args("--sizes 10")).is_err());
+        assert!(parse_args(&args("--solvers teleport")).is_err());
+        assert!(parse_args(&args("--braid 2")).is_err());
+        assert!(parse_args(&args("--csv")).is_err());
+    }
+
+    #[test]
+    fn test_run_is_repeatable_and_exports_every_row() {
+        let config = BenchConfig {
+            sizes: vec![(12, 8)],
+            seeds: vec![7],
+            solvers: vec![PathfindingAlgorithms::Astar, PathfindingAlgorithms::Bfs, PathfindingAlgorithms::Tremaux],
+            ..BenchConfig::default()
+        };
+        let first = run(&config);
+        let second = run(&config);
+        assert_eq!(first.len(), MazeAlgorithms::ALL.len() * 3);
+
+        // The same seed gives the same mazes, so everything but the timings matches
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!((a.expanded, a.path_length, a.peak_memory), (b.expanded, b.path_length, b.peak_memory));
+        }
+
+        assert_eq!(to_csv(&first).lines().count(), first.len() + 1);
+        let json = to_json(&first);
+        assert!(json.starts_with('[') && json.trim_end().ends_with(']'));
+        assert_eq!(json.matches("\"solver\"").count(), first.len());
+        assert_eq!(summary(&first).lines().count(), first.len() + 1);
+    }
+}
@@ -1,4 +1,8 @@
 // lib.rs
 
 mod app;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
 pub use app::Main;
+// For the benchmarks in benches/
+pub use app::{Maze, MazeAlgorithms, PathfindingAlgorithms, SolverOptions, WindowState};